@@ -1,88 +1,698 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::{Debug, Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
-use log::debug;
-use tui_logger::TuiWidgetState;
+use log::{debug, error, info};
+use stick::Event;
+use tui::layout::Rect;
+use tui_logger::{TuiWidgetEvent, TuiWidgetState};
 
-use crate::stick::CtlState;
+use crate::stick::{axis_of, Ctl1Event, CtlInfo, CtlState, SourceFailure};
+use crate::app::rumble::RumbleTest;
+use crate::doctor::Diagnosis;
+use crate::stick::command::{CtlCommand, CtlCommands, RumblePattern};
+use crate::stick::pipeline::CtlPipelineStats;
+use crate::stick::replay::ReplayControl;
+use crate::stick::trace::{default_trace_path, TraceRecorder};
 use crate::ui::action::{UiAction, UiActions};
+use crate::ui::events::UiMouse;
 use crate::ui::key::UiKey;
 
+pub mod rumble;
+
+pub const HISTORY_CAPACITY: usize = 512;
+const HISTORY_SCROLL_STEP: usize = 10;
+pub const AXIS_WINDOW: Duration = Duration::from_secs(10);
+const AXIS_SAMPLES_CAPACITY: usize = 4096;
+pub const DEFAULT_DEADZONE: f64 = 0.1;
+const DEADZONE_STEP: f64 = 0.01;
+const MOUSE_SCROLL_STEP: usize = 3;
+/// What clicking a rumble button sends.
+pub const RUMBLE_CLICK: CtlCommand = CtlCommand::Rumble {
+    left: 1.0,
+    right: 1.0,
+    duration: Duration::from_millis(500),
+    pattern: RumblePattern::Constant,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CtlView {
+    Table,
+    Charts,
+    Sticks,
+    Layout,
+    Raw,
+}
+
+impl CtlView {
+    pub fn next(self) -> Self {
+        match self {
+            CtlView::Table => CtlView::Charts,
+            CtlView::Charts => CtlView::Sticks,
+            CtlView::Sticks => CtlView::Layout,
+            CtlView::Layout => CtlView::Raw,
+            CtlView::Raw => CtlView::Table,
+        }
+    }
+}
+
+/// Where things were drawn last, for mouse hit testing.
+#[derive(Debug, Clone, Default)]
+pub struct UiAreas {
+    pub panels: Vec<(u64, Rect)>,
+    pub rumble_buttons: Vec<(u64, Rect)>,
+    pub timelines: Vec<Rect>,
+    pub log: Option<Rect>,
+}
+
+impl UiAreas {
+    fn ctl_at(areas: &[(u64, Rect)], x: u16, y: u16) -> Option<u64> {
+        areas
+            .iter()
+            .find(|(_, area)| contains(area, x, y))
+            .map(|(id, _)| *id)
+    }
+
+    fn is_timeline(&self, x: u16, y: u16) -> bool {
+        self.timelines.iter().any(|area| contains(area, x, y))
+    }
+
+    fn is_log(&self, x: u16, y: u16) -> bool {
+        self.log.is_some_and(|area| contains(&area, x, y))
+    }
+}
+
+fn contains(area: &Rect, x: u16, y: u16) -> bool {
+    x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+}
+
 pub struct AppTui {
     actions: UiActions,
     pub tui: TuiWidgetState,
+    /// View of each controller panel, `CtlView::Table` until cycled.
+    pub views: HashMap<u64, CtlView>,
+    pub deadzone: f64,
+    pub timeline: bool,
+    pub timeline_scroll: usize,
+    pub focused: Option<u64>,
+    pub zoomed: bool,
+    pub help: bool,
+    /// The diagnostics report while shown, taken when opened.
+    pub diagnostics: Option<Vec<String>>,
+    pub rumble_panel: bool,
+    pub rumble: RumbleTest,
+    pub areas: UiAreas,
 }
 
 impl AppTui {
-    pub fn new() -> Self {
+    pub fn new(actions: UiActions) -> Self {
         Self {
-            actions: UiAction::iterator().cloned().collect::<Vec<_>>().into(),
+            actions,
             tui: TuiWidgetState::default(),
+            views: HashMap::new(),
+            deadzone: DEFAULT_DEADZONE,
+            timeline: true,
+            timeline_scroll: 0,
+            focused: None,
+            zoomed: false,
+            help: false,
+            diagnostics: None,
+            rumble_panel: false,
+            rumble: RumbleTest::default(),
+            areas: UiAreas::default(),
         }
     }
 
-    pub async fn do_action(&mut self, key: UiKey) -> AppReturn {
+    pub async fn do_action(&mut self, key: UiKey, app: &Arc<Mutex<AppState>>) -> AppReturn {
+        if key == UiKey::Esc && (self.help || self.diagnostics.is_some()) {
+            self.help = false;
+            self.diagnostics = None;
+            return AppReturn::Continue;
+        }
+
         if let Some(action) = self.actions.find(key) {
             debug!("run action [{}]", action);
             match action {
                 UiAction::Quit => AppReturn::Exit,
+                UiAction::CycleView => {
+                    if self.focused.is_none() {
+                        self.focused = app.lock().unwrap().ids().first().cloned();
+                    }
+                    if let Some(id) = self.focused {
+                        let view = self.view(id).next();
+                        self.views.insert(id, view);
+                        app.lock().unwrap().commands.keep_raw_events(id, view == CtlView::Raw);
+                    }
+                    AppReturn::Continue
+                }
+                UiAction::DeadzoneUp => {
+                    self.deadzone = (self.deadzone + DEADZONE_STEP).min(1.0);
+                    AppReturn::Continue
+                }
+                UiAction::DeadzoneDown => {
+                    self.deadzone = (self.deadzone - DEADZONE_STEP).max(0.0);
+                    AppReturn::Continue
+                }
+                UiAction::ToggleTimeline => {
+                    self.timeline = !self.timeline;
+                    AppReturn::Continue
+                }
+                UiAction::ToggleRecording => {
+                    app.lock().unwrap().toggle_recording();
+                    AppReturn::Continue
+                }
+                UiAction::ReplayPause => {
+                    self.replay(app, ReplayControl::toggle_pause);
+                    AppReturn::Continue
+                }
+                UiAction::ReplayStep => {
+                    self.replay(app, ReplayControl::step);
+                    AppReturn::Continue
+                }
+                UiAction::ReplayFaster => {
+                    self.replay(app, ReplayControl::faster);
+                    AppReturn::Continue
+                }
+                UiAction::ReplaySlower => {
+                    self.replay(app, ReplayControl::slower);
+                    AppReturn::Continue
+                }
+                UiAction::FocusPrevious => {
+                    self.move_focus(&app.lock().unwrap().ids(), -1);
+                    AppReturn::Continue
+                }
+                UiAction::FocusNext => {
+                    self.move_focus(&app.lock().unwrap().ids(), 1);
+                    AppReturn::Continue
+                }
+                UiAction::ToggleZoom => {
+                    if self.focused.is_none() {
+                        self.focused = app.lock().unwrap().ids().first().cloned();
+                    }
+                    self.zoomed = self.focused.is_some() && !self.zoomed;
+                    AppReturn::Continue
+                }
+                UiAction::ToggleHelp => {
+                    self.help = !self.help;
+                    AppReturn::Continue
+                }
+                UiAction::ToggleDiagnostics => {
+                    self.diagnostics = match self.diagnostics {
+                        Some(_) => None,
+                        None => Some(Diagnosis::run().lines()),
+                    };
+                    AppReturn::Continue
+                }
+                UiAction::ToggleRumble => {
+                    self.rumble_panel = !self.rumble_panel;
+                    AppReturn::Continue
+                }
+                UiAction::RumblePrevious if self.rumble_panel => {
+                    self.rumble.select(-1);
+                    AppReturn::Continue
+                }
+                UiAction::RumbleNext if self.rumble_panel => {
+                    self.rumble.select(1);
+                    AppReturn::Continue
+                }
+                UiAction::RumbleLess if self.rumble_panel => {
+                    self.rumble.adjust(false);
+                    AppReturn::Continue
+                }
+                UiAction::RumbleMore if self.rumble_panel => {
+                    self.rumble.adjust(true);
+                    AppReturn::Continue
+                }
+                UiAction::RumbleFire if self.rumble_panel => {
+                    self.command(app, self.rumble.command());
+                    AppReturn::Continue
+                }
+                UiAction::RumblePrevious
+                | UiAction::RumbleNext
+                | UiAction::RumbleLess
+                | UiAction::RumbleMore
+                | UiAction::RumbleFire => {
+                    debug!("rumble panel not shown");
+                    AppReturn::Continue
+                }
+                UiAction::RumbleStop => {
+                    self.command(app, CtlCommand::StopRumble);
+                    AppReturn::Continue
+                }
+                UiAction::ScrollUp => {
+                    self.scroll_up(HISTORY_SCROLL_STEP);
+                    AppReturn::Continue
+                }
+                UiAction::ScrollDown => {
+                    self.scroll_down(HISTORY_SCROLL_STEP);
+                    AppReturn::Continue
+                }
+                UiAction::ScrollEnd => {
+                    self.timeline_scroll = 0;
+                    AppReturn::Continue
+                }
             }
         } else {
             debug!("no action associated with key [{}]", key);
             AppReturn::Continue
         }
     }
+
+    pub async fn do_mouse(&mut self, mouse: UiMouse, app: &Arc<Mutex<AppState>>) -> AppReturn {
+        match mouse {
+            UiMouse::Click(x, y) => {
+                if let Some(id) = UiAreas::ctl_at(&self.areas.rumble_buttons, x, y) {
+                    self.focused = Some(id);
+                    if !app.lock().unwrap().commands.send(id, RUMBLE_CLICK) {
+                        debug!("ctl={} does not take commands", id);
+                    }
+                } else if let Some(id) = UiAreas::ctl_at(&self.areas.panels, x, y) {
+                    self.focused = Some(id);
+                }
+            }
+            UiMouse::ScrollUp(x, y) if self.areas.is_timeline(x, y) => {
+                self.scroll_up(MOUSE_SCROLL_STEP);
+            }
+            UiMouse::ScrollDown(x, y) if self.areas.is_timeline(x, y) => {
+                self.scroll_down(MOUSE_SCROLL_STEP);
+            }
+            UiMouse::ScrollUp(x, y) if self.areas.is_log(x, y) => {
+                self.tui.transition(&TuiWidgetEvent::PrevPageKey);
+            }
+            UiMouse::ScrollDown(x, y) if self.areas.is_log(x, y) => {
+                self.tui.transition(&TuiWidgetEvent::NextPageKey);
+            }
+            _ => {}
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Focuses the controller `by` places from the focused one, wrapping
+    /// around. Without focus, moving forward focuses the first controller and
+    /// moving backward the last.
+    fn move_focus(&mut self, ids: &[u64], by: isize) {
+        if ids.is_empty() {
+            return;
+        }
+
+        let len = ids.len() as isize;
+        let index = match self.focused.and_then(|id| ids.iter().position(|i| *i == id)) {
+            Some(index) => (index as isize + by).rem_euclid(len),
+            None if by > 0 => 0,
+            None => len - 1,
+        };
+        self.focused = Some(ids[index as usize]);
+    }
+
+    pub fn actions(&self) -> &UiActions {
+        &self.actions
+    }
+
+    fn scroll_up(&mut self, step: usize) {
+        self.timeline_scroll = (self.timeline_scroll + step).min(HISTORY_CAPACITY);
+    }
+
+    fn scroll_down(&mut self, step: usize) {
+        self.timeline_scroll = self.timeline_scroll.saturating_sub(step);
+    }
+
+    /// Drops the view and focus of controllers the app state forgot.
+    pub fn forget(&mut self, ids: &[u64]) {
+        for id in ids {
+            self.views.remove(id);
+            if self.focused == Some(*id) {
+                self.focused = None;
+                self.zoomed = false;
+            }
+        }
+    }
+
+    /// How the panel of controller `id` is shown.
+    pub fn view(&self, id: u64) -> CtlView {
+        self.views.get(&id).copied().unwrap_or(CtlView::Table)
+    }
+
+    /// Sends `command` to the focused controller, focusing the first one if
+    /// none is.
+    fn command(&mut self, app: &Arc<Mutex<AppState>>, command: CtlCommand) {
+        let app = app.lock().unwrap();
+        if self.focused.is_none() {
+            self.focused = app.ids().first().cloned();
+        }
+
+        match self.focused {
+            Some(id) if app.commands.send(id, command) => {}
+            Some(id) => debug!("ctl={} does not take commands", id),
+            None => debug!("no controller to send {:?} to", command),
+        }
+    }
+
+    fn replay<F>(&self, app: &Arc<Mutex<AppState>>, f: F)
+        where F: FnOnce(&ReplayControl) {
+        match &app.lock().unwrap().replay {
+            Some(replay) => f(replay),
+            None => debug!("not replaying"),
+        }
+    }
 }
 
 impl Default for AppTui {
     fn default() -> Self {
-        Self::new()
+        Self::new(UiActions::default())
+    }
+}
+
+
+/// Bounded, oldest-first record of the events a controller produced.
+#[derive(Clone, Debug)]
+pub struct CtlHistory {
+    pub started: Instant,
+    pub events: VecDeque<Ctl1Event>,
+}
+
+impl CtlHistory {
+    fn new(started: Instant) -> Self {
+        Self {
+            started,
+            events: VecDeque::with_capacity(HISTORY_CAPACITY),
+        }
+    }
+
+    fn push(&mut self, ctl_event: Ctl1Event) {
+        if self.events.len() == HISTORY_CAPACITY {
+            self.events.pop_front();
+        }
+        self.events.push_back(ctl_event);
     }
 }
 
 
+/// Recent samples of one analog input, covering at least `AXIS_WINDOW`.
 #[derive(Clone, Debug)]
+pub struct AxisSamples {
+    pub name: &'static str,
+    pub samples: VecDeque<(Instant, f64)>,
+}
+
+impl AxisSamples {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            samples: VecDeque::with_capacity(64),
+        }
+    }
+
+    fn push(&mut self, at: Instant, value: f64) {
+        // Keep the last sample older than the window, it holds the value at
+        // the start of the window.
+        while self.samples.len() > 1
+            && at.saturating_duration_since(self.samples[1].0) > AXIS_WINDOW {
+            self.samples.pop_front();
+        }
+        if self.samples.len() == AXIS_SAMPLES_CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, value));
+    }
+
+    /// Points of the window ending at `now`, x in seconds relative to `now`.
+    pub fn window(&self, now: Instant) -> Vec<(f64, f64)> {
+        let window = AXIS_WINDOW.as_secs_f64();
+        let mut points = Vec::with_capacity(self.samples.len() + 1);
+
+        for (at, value) in self.samples.iter() {
+            let x = -now.saturating_duration_since(*at).as_secs_f64();
+            if x < -window {
+                points.clear();
+                points.push((-window, *value));
+            } else {
+                points.push((x, *value));
+            }
+        }
+
+        if let Some((_, value)) = self.samples.back() {
+            points.push((0.0, *value));
+        }
+
+        points
+    }
+
+    /// Min, max and mean of the value over the window ending at `now`. The
+    /// mean is weighted by how long each value was held.
+    pub fn stats(&self, now: Instant) -> Option<(f64, f64, f64)> {
+        let points = self.window(now);
+        let (start, last) = match (points.first(), points.last()) {
+            (Some((start, _)), Some((_, last))) => (*start, *last),
+            _ => return None,
+        };
+
+        let mut min = f64::MAX;
+        let mut max = f64::MIN;
+        let mut area = 0.0;
+        for (i, (x, value)) in points.iter().enumerate() {
+            min = min.min(*value);
+            max = max.max(*value);
+            if let Some((next, _)) = points.get(i + 1) {
+                area += value * (next - x);
+            }
+        }
+
+        // All samples at `now`: nothing was held yet.
+        let mean = if start < 0.0 { area / -start } else { last };
+        Some((min, max, mean))
+    }
+}
+
+
+/// When a controller disconnected, kept until it reconnects or is forgotten.
+#[derive(Debug, Clone, Copy)]
+pub struct Disconnected {
+    pub at: Instant,
+    pub wall: SystemTime,
+}
+
+
+#[derive(Debug)]
 pub struct AppState {
     pub previous: HashMap<u64, CtlState>,
     pub current: HashMap<u64, CtlState>,
-    pub name_mapping: HashMap<u64, String>,
+    pub info: HashMap<u64, CtlInfo>,
+    pub pipeline: HashMap<u64, CtlPipelineStats>,
+    pub history: HashMap<u64, CtlHistory>,
+    pub axes: HashMap<u64, Vec<AxisSamples>>,
+    pub disconnected: HashMap<u64, Disconnected>,
+    /// Disconnected controllers are removed after this long, if set.
+    pub forget_after: Option<Duration>,
+    pub recorder: Option<TraceRecorder>,
+    pub replay: Option<ReplayControl>,
+    pub commands: CtlCommands,
+    pub source_failure: SourceFailure,
 }
 
 impl AppState {
-    fn ensure_name(&mut self, id: u64, name: String) {
-        self.name_mapping
-            .entry(id)
-            .or_insert(name);
+    fn ensure_info(&mut self, info: CtlInfo) {
+        self.info
+            .entry(info.id)
+            .or_insert(info);
     }
 
     fn ensure_state(&mut self, id: u64) {
-        self.previous.entry(id).or_insert_with(CtlState::default);
-        self.current.entry(id).or_insert_with(CtlState::default);
+        self.previous.entry(id).or_default();
+        self.current.entry(id).or_default();
     }
 
-    pub fn ensure_ctl(&mut self, id: u64, name: String) {
-        self.ensure_name(id, name);
+    pub fn ensure_ctl(&mut self, info: CtlInfo) {
+        let id = info.id;
+        self.ensure_info(info);
         self.ensure_state(id);
     }
+
+    pub fn update(&mut self, ctl_event: Ctl1Event) {
+        let id = ctl_event.ctl_id;
+        self.ensure_ctl(ctl_event.info());
+        self.record(&ctl_event);
+
+        match ctl_event.triggering_event {
+            Event::Disconnect => {
+                let disconnected = Disconnected {
+                    at: ctl_event.at,
+                    wall: SystemTime::now(),
+                };
+                self.disconnected.insert(id, disconnected);
+            }
+            _ => {
+                if self.disconnected.remove(&id).is_some() {
+                    info!("reconnected ctl={}", ctl_event.info());
+                }
+                // The device node may have changed on reconnect.
+                if self.info[&id].path != ctl_event.ctl_path {
+                    self.info.insert(id, ctl_event.info());
+                }
+            }
+        }
+
+        let previous = self.current.remove(&id).unwrap();
+        let current = previous.updated(ctl_event.triggering_event);
+        self.previous.insert(id, previous);
+        self.current.insert(id, current);
+
+        if let Some((name, value)) = axis_of(&ctl_event.triggering_event) {
+            let axes = self.axes.entry(id).or_default();
+            let index = match axes.iter().position(|axis| axis.name == name) {
+                Some(index) => index,
+                None => {
+                    axes.push(AxisSamples::new(name));
+                    axes.len() - 1
+                }
+            };
+            axes[index].push(ctl_event.at, value);
+        }
+
+        self.history
+            .entry(id)
+            .or_insert_with(|| CtlHistory::new(ctl_event.at))
+            .push(ctl_event);
+    }
+
+    pub fn start_recording(&mut self, recorder: TraceRecorder) {
+        info!("recording to {}", recorder.path().display());
+        if let Some(previous) = self.recorder.replace(recorder) {
+            finish_recording(previous);
+        }
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            finish_recording(recorder);
+        }
+    }
+
+    /// Writes out the recorded events that waited too long for the next
+    /// one.
+    fn flush_recording(&mut self, now: Instant) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = recorder.flush_due(now) {
+                error!("recording to {} stopped: {:#}", recorder.path().display(), err);
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Stops the running recording, or starts one in a new file.
+    pub fn toggle_recording(&mut self) {
+        if self.recorder.is_some() {
+            self.stop_recording();
+            return;
+        }
+
+        match TraceRecorder::create(default_trace_path()) {
+            Ok(recorder) => self.start_recording(recorder),
+            Err(err) => error!("{:#}", err),
+        }
+    }
+
+    fn record(&mut self, ctl_event: &Ctl1Event) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = recorder.record(ctl_event) {
+                error!("recording to {} stopped: {:#}", recorder.path().display(), err);
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Housekeeping between two frames, returns the ids of the controllers
+    /// it forgot.
+    pub fn tick(&mut self, now: Instant) -> Vec<u64> {
+        self.reattach();
+        let forgotten = self.forget_disconnected(now);
+        self.flush_recording(now);
+        forgotten
+    }
+
+    /// Clears the disconnected mark of the controllers that take commands
+    /// again: they reconnected, even if they have not sent an event yet.
+    fn reattach(&mut self) {
+        let commands = &self.commands;
+        self.disconnected.retain(|id, _| {
+            let reconnected = commands.accepts(*id);
+            if reconnected {
+                info!("reconnected ctl=#{}", id);
+            }
+            !reconnected
+        });
+    }
+
+    /// Removes the controllers disconnected for longer than `forget_after`,
+    /// returns their ids.
+    fn forget_disconnected(&mut self, now: Instant) -> Vec<u64> {
+        let after = match self.forget_after {
+            Some(after) => after,
+            None => return Vec::new(),
+        };
+
+        let expired = self.disconnected
+            .iter()
+            .filter(|(_, disconnected)| now.saturating_duration_since(disconnected.at) >= after)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        for id in expired.iter() {
+            debug!("forgetting disconnected ctl={}", id);
+            self.previous.remove(id);
+            self.current.remove(id);
+            self.info.remove(id);
+            self.pipeline.remove(id);
+            self.history.remove(id);
+            self.axes.remove(id);
+            self.disconnected.remove(id);
+        }
+        expired
+    }
+
+    /// Controller ids in connection order, one per physical device.
+    pub fn ids(&self) -> Vec<u64> {
+        let mut ids = self.current.keys().cloned().collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    }
 }
 
 impl Display for AppState {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut names = "".to_string();
-        for name in self.name_mapping.values() {
-            names.push_str(name);
+        for info in self.info.values() {
+            names.push_str(&info.name);
             names.push(',');
         }
         write!(f, "AppState[ctls=({})]", names)
     }
 }
 
+fn finish_recording(recorder: TraceRecorder) {
+    let (path, records) = (recorder.path().to_path_buf(), recorder.records());
+    match recorder.finish() {
+        Ok(()) => info!("stopped recording, {} events in {}", records, path.display()),
+        Err(err) => error!("recording to {} could not be finished: {:#}", path.display(), err),
+    }
+}
+
 impl Default for AppState {
     fn default() -> Self {
         Self {
             previous: HashMap::with_capacity(2),
             current: HashMap::with_capacity(2),
-            name_mapping: HashMap::with_capacity(2),
+            info: HashMap::with_capacity(2),
+            pipeline: HashMap::with_capacity(2),
+            history: HashMap::with_capacity(2),
+            axes: HashMap::with_capacity(2),
+            disconnected: HashMap::new(),
+            forget_after: None,
+            recorder: None,
+            replay: None,
+            commands: CtlCommands::default(),
+            source_failure: SourceFailure::default(),
         }
     }
 }
@@ -93,3 +703,146 @@ pub enum AppReturn {
     Exit,
     Continue,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::stick::source::{read_source, EventsSource};
+
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[tokio::test]
+    async fn update_follows_the_events_of_a_source() {
+        let events = vec![
+            Ctl1Event::new(Event::ActionA(true), &CtlInfo::fixture(1)),
+            Ctl1Event::new(Event::ActionB(true), &CtlInfo::fixture(2)),
+            Ctl1Event::new(Event::ActionA(false), &CtlInfo::fixture(1)),
+        ];
+        let (mut rx, handle) = read_source(Box::new(EventsSource(events)));
+
+        let mut app = AppState::default();
+        while let Some(ctl_event) = rx.recv().await {
+            app.update(ctl_event);
+        }
+        handle.await.unwrap();
+
+        assert_eq!(app.ids(), vec![1, 2]);
+        assert_eq!(app.info[&2], CtlInfo::fixture(2));
+        assert_eq!(app.previous[&1].action_a, Some(true));
+        assert_eq!(app.current[&1].action_a, Some(false));
+        assert_eq!(app.current[&2].action_b, Some(true));
+
+        let history = app.history[&1].events.iter()
+            .map(|ctl_event| format!("{:?}", ctl_event.triggering_event))
+            .collect::<Vec<_>>();
+        assert_eq!(history, vec!["ActionA(true)", "ActionA(false)"]);
+        assert_eq!(app.history[&2].events.len(), 1);
+    }
+
+    #[test]
+    fn history_drops_the_oldest_events() {
+        let mut app = AppState::default();
+        for i in 0..HISTORY_CAPACITY + 2 {
+            app.update(Ctl1Event::new(Event::ActionA(i % 2 == 0), &CtlInfo::fixture(1)));
+        }
+
+        let history = &app.history[&1].events;
+        assert_eq!(history.len(), HISTORY_CAPACITY);
+        assert!(matches!(history.front().unwrap().triggering_event, Event::ActionA(true)));
+        assert!(matches!(history.back().unwrap().triggering_event, Event::ActionA(false)));
+    }
+
+    #[test]
+    fn axis_window_starts_with_the_value_held_at_its_start() {
+        let start = Instant::now();
+        let mut samples = AxisSamples::new("Joy X");
+        samples.push(start, 0.5);
+        samples.push(start + secs(2), -0.5);
+        samples.push(start + secs(8), 1.0);
+
+        let now = start + AXIS_WINDOW + secs(5);
+        assert_eq!(samples.window(now), vec![
+            (-10.0, -0.5),
+            (-7.0, 1.0),
+            (0.0, 1.0),
+        ]);
+        // -0.5 for 3s, then 1.0 for 7s.
+        assert_eq!(samples.stats(now), Some((-0.5, 1.0, 0.55)));
+    }
+
+    #[test]
+    fn axis_samples_older_than_the_window_are_dropped() {
+        let start = Instant::now();
+        let mut samples = AxisSamples::new("Joy X");
+        samples.push(start, 0.1);
+        samples.push(start + secs(1), 0.2);
+        samples.push(start + secs(2), 0.3);
+        samples.push(start + AXIS_WINDOW + secs(3), 0.4);
+
+        // The sample at 2s is kept: it is the value when the window starts.
+        let kept = samples.samples.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+        assert_eq!(kept, vec![0.3, 0.4]);
+    }
+
+    #[test]
+    fn axis_stats_of_samples_at_now() {
+        let now = Instant::now();
+        let mut samples = AxisSamples::new("Joy X");
+        samples.push(now, 0.25);
+
+        assert_eq!(samples.stats(now), Some((0.25, 0.25, 0.25)));
+    }
+
+    #[test]
+    fn axis_stats_of_no_samples() {
+        assert_eq!(AxisSamples::new("Joy X").stats(Instant::now()), None);
+    }
+
+    #[test]
+    fn update_samples_each_axis() {
+        let mut app = AppState::default();
+        for event in [Event::JoyX(0.1), Event::TriggerL(0.5), Event::JoyX(0.2)] {
+            app.update(Ctl1Event::new(event, &CtlInfo::fixture(1)));
+        }
+
+        let axes = &app.axes[&1];
+        assert_eq!(axes.iter().map(|axis| axis.name).collect::<Vec<_>>(), vec!["Joy X", "Trigger L"]);
+        assert_eq!(axes[0].samples.iter().map(|(_, value)| *value).collect::<Vec<_>>(), vec![0.1, 0.2]);
+    }
+
+    #[test]
+    fn disconnected_controllers_are_forgotten_after_a_while() {
+        let mut app = AppState {
+            forget_after: Some(secs(5)),
+            ..AppState::default()
+        };
+        let disconnect = Ctl1Event::new(Event::Disconnect, &CtlInfo::fixture(1));
+        let at = disconnect.at;
+        app.update(disconnect);
+        assert!(app.current[&1].disconnected);
+        assert!(app.disconnected.contains_key(&1));
+
+        assert!(app.tick(at + secs(4)).is_empty());
+        assert_eq!(app.ids(), vec![1]);
+        assert_eq!(app.tick(at + secs(5)), vec![1]);
+        assert!(app.ids().is_empty());
+        assert!(app.info.is_empty() && app.history.is_empty() && app.disconnected.is_empty());
+    }
+
+    #[test]
+    fn forgotten_controllers_lose_their_view_and_focus() {
+        let mut app_tui = AppTui::default();
+        app_tui.views.insert(1, CtlView::Raw);
+        app_tui.views.insert(2, CtlView::Charts);
+        app_tui.focused = Some(1);
+        app_tui.zoomed = true;
+
+        app_tui.forget(&[1]);
+        assert_eq!(app_tui.views.keys().collect::<Vec<_>>(), vec![&2]);
+        assert_eq!(app_tui.focused, None);
+        assert!(!app_tui.zoomed);
+    }
+}
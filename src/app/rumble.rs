@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use crate::stick::command::{CtlCommand, RumblePattern};
+
+const POWER_STEP: f32 = 0.1;
+const DURATION_STEP: Duration = Duration::from_millis(100);
+const DURATION_MAX: Duration = Duration::from_secs(10);
+const PERIOD_STEP: Duration = Duration::from_millis(50);
+const PERIOD_MAX: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RumbleField {
+    Left,
+    Right,
+    Duration,
+    Pattern,
+    Period,
+}
+
+impl RumbleField {
+    pub const ALL: [RumbleField; 5] = [
+        RumbleField::Left,
+        RumbleField::Right,
+        RumbleField::Duration,
+        RumbleField::Pattern,
+        RumbleField::Period,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RumbleField::Left => "Left",
+            RumbleField::Right => "Right",
+            RumbleField::Duration => "Duration",
+            RumbleField::Pattern => "Pattern",
+            RumbleField::Period => "Period",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RumbleKind {
+    Constant,
+    Pulse,
+    Alternate,
+}
+
+impl RumbleKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RumbleKind::Constant => "constant",
+            RumbleKind::Pulse => "pulse",
+            RumbleKind::Alternate => "alternate",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            RumbleKind::Constant => RumbleKind::Pulse,
+            RumbleKind::Pulse => RumbleKind::Alternate,
+            RumbleKind::Alternate => RumbleKind::Constant,
+        }
+    }
+
+    fn previous(self) -> Self {
+        self.next().next()
+    }
+}
+
+/// Settings of the rumble test panel, sent to the focused controller.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumbleTest {
+    pub field: RumbleField,
+    pub left: f32,
+    pub right: f32,
+    pub duration: Duration,
+    pub kind: RumbleKind,
+    pub period: Duration,
+}
+
+impl RumbleTest {
+    pub fn select(&mut self, by: isize) {
+        let fields = RumbleField::ALL;
+        let index = fields.iter().position(|field| *field == self.field).unwrap_or_default();
+        let index = (index as isize + by).rem_euclid(fields.len() as isize);
+        self.field = fields[index as usize];
+    }
+
+    /// Raises the selected setting if `up`, lowers it otherwise.
+    pub fn adjust(&mut self, up: bool) {
+        match self.field {
+            RumbleField::Left => self.left = step_power(self.left, up),
+            RumbleField::Right => self.right = step_power(self.right, up),
+            RumbleField::Duration => {
+                self.duration = step_duration(self.duration, DURATION_STEP, DURATION_MAX, up);
+            }
+            RumbleField::Pattern => {
+                self.kind = if up { self.kind.next() } else { self.kind.previous() };
+            }
+            RumbleField::Period => {
+                self.period = step_duration(self.period, PERIOD_STEP, PERIOD_MAX, up);
+            }
+        }
+    }
+
+    pub fn pattern(&self) -> RumblePattern {
+        match self.kind {
+            RumbleKind::Constant => RumblePattern::Constant,
+            RumbleKind::Pulse => RumblePattern::Pulse(self.period),
+            RumbleKind::Alternate => RumblePattern::Alternate(self.period),
+        }
+    }
+
+    pub fn command(&self) -> CtlCommand {
+        CtlCommand::Rumble {
+            left: self.left,
+            right: self.right,
+            duration: self.duration,
+            pattern: self.pattern(),
+        }
+    }
+}
+
+impl Default for RumbleTest {
+    fn default() -> Self {
+        Self {
+            field: RumbleField::Left,
+            left: 1.0,
+            right: 1.0,
+            duration: Duration::from_millis(1000),
+            kind: RumbleKind::Constant,
+            period: Duration::from_millis(200),
+        }
+    }
+}
+
+fn step_power(power: f32, up: bool) -> f32 {
+    let power = if up { power + POWER_STEP } else { power - POWER_STEP };
+    // Snap to the step, so repeated steps do not drift.
+    ((power / POWER_STEP).round() * POWER_STEP).clamp(0.0, 1.0)
+}
+
+fn step_duration(duration: Duration, step: Duration, max: Duration, up: bool) -> Duration {
+    if up {
+        (duration + step).min(max)
+    } else {
+        duration.saturating_sub(step).max(step)
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+use log::LevelFilter;
+
+use crate::monitor::MonitorOptions;
+
+#[derive(Debug, Clone, Parser)]
+#[command(name = "ctl1", version, about = "A terminal UI for joysticks")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// UI refresh interval, in milliseconds.
+    #[arg(long, global = true, default_value_t = 40)]
+    pub tick_rate: u64,
+
+    /// off, error, warn, info, debug or trace. Defaults to $CTL1_LOG, then
+    /// info.
+    #[arg(long, global = true, value_parser = parse_level)]
+    pub log_level: Option<LevelFilter>,
+
+    /// Also write the log to this file.
+    #[arg(long, global = true)]
+    pub log_file: Option<PathBuf>,
+
+    /// Remove disconnected controllers from the UI after this many seconds.
+    #[arg(long, global = true, value_parser = parse_seconds)]
+    pub forget_after: Option<Duration>,
+
+    /// Also connect input devices that do not look like game controllers.
+    #[arg(long, global = true)]
+    pub all_devices: bool,
+
+    /// Config file, defaults to ~/.config/ctl1/config.toml if it exists.
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Only controllers whose name or path contains this text, or `#<id>`.
+    #[arg(long = "ctl", global = true)]
+    pub ctl_filter: Option<String>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Show controllers in the terminal UI (default).
+    Tui,
+    /// Print the connected controllers and exit.
+    List {
+        /// How long to wait for controllers to show up, in milliseconds.
+        #[arg(long, default_value_t = 1000)]
+        wait: u64,
+    },
+    /// Print events as they arrive, without the terminal UI.
+    Monitor(MonitorOptions),
+    /// Check which input devices can be opened, and how to fix the others.
+    Doctor,
+    /// Run the terminal UI and record the session to a trace file.
+    Record {
+        /// Defaults to ctl1-<unix time>.jsonl.
+        path: Option<PathBuf>,
+    },
+    /// Run the terminal UI on a recorded trace instead of connected controllers.
+    Replay {
+        /// A trace written by `ctl1 record`, or by `r` in the terminal UI.
+        path: PathBuf,
+    },
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level.parse().map_err(|_| format!("unknown log level {}", level))
+}
+
+/// A number of seconds, fractions allowed, not negative.
+pub fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    let value: f64 = seconds.parse().map_err(|_| format!("{} is not a number of seconds", seconds))?;
+    Duration::try_from_secs_f64(value).map_err(|_| format!("{} must be a finite number of seconds, 0 or more", seconds))
+}
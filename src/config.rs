@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::ui::action::{UiAction, UiActions};
+use crate::ui::key::UiKey;
+
+/// Settings read from `config.toml`, e.g.:
+///
+/// ```toml
+/// [keys]
+/// quit = ["q", "<Ctrl+c>", "<Esc>"]
+/// cycle_view = ["<F2>"]
+/// ```
+///
+/// Actions missing from `[keys]` keep their default bindings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub keys: HashMap<String, Vec<String>>,
+}
+
+impl Config {
+    pub fn load<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| eyre::eyre!("{}: {}", path.display(), err))?;
+
+        toml::from_str(&text).map_err(|err| eyre::eyre!("{}: {}", path.display(), err))
+    }
+
+    /// `path` if given, else the default config file if there is one, else
+    /// the defaults.
+    pub fn load_or_default(path: Option<&Path>) -> eyre::Result<Self> {
+        match path {
+            Some(path) => Self::load(path),
+            None => match default_config_path() {
+                Some(path) if path.exists() => Self::load(path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    pub fn actions(&self) -> eyre::Result<UiActions> {
+        let mut overrides = HashMap::new();
+
+        for (name, keys) in self.keys.iter() {
+            let action = UiAction::from_name(name)
+                .ok_or_else(|| eyre::eyre!("unknown action {} in [keys]", name))?;
+            let keys = keys
+                .iter()
+                .map(|key| key.parse::<UiKey>())
+                .collect::<eyre::Result<Vec<_>>>()
+                .map_err(|err| eyre::eyre!("keys of {}: {}", name, err))?;
+            overrides.insert(action, keys);
+        }
+
+        UiActions::with_overrides(&overrides)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/ctl1/config.toml`, or `~/.config/ctl1/config.toml`.
+pub fn default_config_path() -> Option<PathBuf> {
+    let dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+
+    Some(dir.join("ctl1").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions(toml: &str) -> eyre::Result<UiActions> {
+        toml::from_str::<Config>(toml).unwrap().actions()
+    }
+
+    #[test]
+    fn overrides_replace_the_default_keys() {
+        let actions = actions("[keys]\nquit = [\"<Esc>\"]\n").unwrap();
+
+        assert_eq!(actions.find(UiKey::Esc), Some(&UiAction::Quit));
+        assert_eq!(actions.find(UiKey::Char('q')), None);
+        assert_eq!(actions.keys_of(UiAction::CycleView), &[UiKey::Char('v')]);
+    }
+
+    #[test]
+    fn conflicts_with_default_keys_are_rejected() {
+        let err = actions("[keys]\nquit = [\"v\"]\n").unwrap_err();
+
+        assert_eq!(err.to_string(), "Conflict key v with actions Action[Quit], Action[CycleView]");
+    }
+
+    #[test]
+    fn conflicts_between_overrides_are_rejected() {
+        let err = actions("[keys]\nquit = [\"<F2>\"]\ncycle_view = [\"<F2>\"]\n").unwrap_err();
+
+        assert!(err.to_string().starts_with("Conflict key <F2> with actions "), "{}", err);
+    }
+
+    #[test]
+    fn moving_a_key_to_another_action_is_no_conflict() {
+        let actions = actions("[keys]\nquit = [\"v\"]\ncycle_view = [\"<F2>\"]\n").unwrap();
+
+        assert_eq!(actions.find(UiKey::Char('v')), Some(&UiAction::Quit));
+        assert_eq!(actions.find(UiKey::F2), Some(&UiAction::CycleView));
+    }
+
+    #[test]
+    fn unknown_actions_and_keys_are_rejected() {
+        assert!(actions("[keys]\nnope = [\"q\"]\n").is_err());
+        assert!(actions("[keys]\nquit = [\"<Nope>\"]\n").is_err());
+    }
+}
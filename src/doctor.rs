@@ -0,0 +1,302 @@
+//! Why controllers may not show up: which input nodes the stick listener
+//! could open, what they are, and what to do about the ones it could not.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use stick::{DeviceAccess, DeviceNode};
+
+const INPUT_DIR: &str = "/dev/input";
+const SYS_INPUT_DIR: &str = "/sys/class/input";
+/// Device or resource busy, e.g. grabbed with EVIOCGRAB.
+const EBUSY: i32 = 16;
+pub const UDEV_RULE_PATH: &str = "/etc/udev/rules.d/70-ctl1.rules";
+/// Gives the logged in user access to game controllers.
+pub const UDEV_RULE: &str =
+    r#"KERNEL=="event*", SUBSYSTEM=="input", ENV{ID_INPUT_JOYSTICK}=="1", TAG+="uaccess""#;
+
+/// One `/dev/input/event*` node.
+#[derive(Debug)]
+pub struct NodeReport {
+    pub path: PathBuf,
+    pub name: Option<String>,
+    /// Whether stick takes it for a game controller, `None` if it could not
+    /// be opened.
+    pub controller: Option<bool>,
+    /// How stick could open it, or the errno if it could not.
+    pub access: Result<DeviceAccess, i32>,
+    pub mode: u32,
+    pub owner: String,
+    pub group: String,
+    gid: u32,
+}
+
+#[derive(Debug)]
+pub struct Diagnosis {
+    pub nodes: Vec<NodeReport>,
+    /// Why `/dev/input` could not be listed, if it could not.
+    pub dir_error: Option<io::Error>,
+    pub groups: Vec<String>,
+    pub hints: Vec<String>,
+}
+
+impl Diagnosis {
+    /// Reports what the stick listener found when it tried each node. When
+    /// no listener ran yet (`ctl1 doctor`), the nodes are tried the same way
+    /// first.
+    pub fn run() -> Self {
+        let names = id_names("/etc/group");
+        let users = id_names("/etc/passwd");
+        let gids = user_gids();
+        let groups = gids
+            .iter()
+            .map(|gid| names.get(gid).cloned().unwrap_or_else(|| gid.to_string()))
+            .collect();
+
+        let tried = match stick::tried_devices() {
+            tried if tried.is_empty() => stick::probe_devices(),
+            tried => Ok(tried),
+        };
+        let (nodes, dir_error) = match tried {
+            Ok(tried) => {
+                let mut nodes = tried
+                    .into_iter()
+                    .map(|node| NodeReport::new(node, &names, &users))
+                    .collect::<Vec<_>>();
+                nodes.sort_by_key(|node| event_number(&node.path));
+                (nodes, None)
+            }
+            Err(err) => (Vec::new(), Some(err)),
+        };
+
+        let hints = hints(&nodes, dir_error.as_ref(), &gids);
+
+        Self {
+            nodes,
+            dir_error,
+            groups,
+            hints,
+        }
+    }
+
+    /// The report, as printed by `ctl1 doctor`.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::with_capacity(self.nodes.len() + 16);
+
+        if let Some(err) = &self.dir_error {
+            lines.push(format!("{}: {}", INPUT_DIR, err));
+        }
+        for node in self.nodes.iter() {
+            lines.push(node.line());
+        }
+
+        lines.push(String::new());
+        lines.push(format!("Your groups: {}", self.groups.join(", ")));
+
+        if !self.hints.is_empty() {
+            lines.push(String::new());
+            for hint in self.hints.iter() {
+                lines.push(format!("- {}", hint));
+            }
+        }
+
+        lines
+    }
+}
+
+impl NodeReport {
+    fn new(node: DeviceNode, groups: &HashMap<u32, String>, users: &HashMap<u32, String>) -> Self {
+        let path = PathBuf::from(node.path);
+        let metadata = fs::metadata(&path).ok();
+        let (mode, uid, gid) = metadata
+            .map(|metadata| (metadata.permissions().mode(), metadata.uid(), metadata.gid()))
+            .unwrap_or_default();
+
+        let sys = path
+            .file_name()
+            .map(|name| Path::new(SYS_INPUT_DIR).join(name).join("device"))
+            .unwrap_or_default();
+        let name = fs::read_to_string(sys.join("name")).ok().map(|name| name.trim().to_string());
+
+        Self {
+            access: node.access,
+            controller: node.controller,
+            name,
+            mode,
+            owner: users.get(&uid).cloned().unwrap_or_else(|| uid.to_string()),
+            group: groups.get(&gid).cloned().unwrap_or_else(|| gid.to_string()),
+            gid,
+            path,
+        }
+    }
+
+    fn denied(&self) -> bool {
+        matches!(self.access, Err(errno) if is_permission(errno))
+    }
+
+    fn line(&self) -> String {
+        let access = match self.access {
+            Ok(DeviceAccess::ReadWrite) => "ok".to_string(),
+            Ok(DeviceAccess::ReadOnly) => "read-only".to_string(),
+            Ok(DeviceAccess::WriteOnly) => "write-only".to_string(),
+            Err(errno) => format!("{} {}", errno_name(errno), io::Error::from_raw_os_error(errno).kind()),
+        };
+        let controller = match self.controller {
+            Some(true) => "controller",
+            Some(false) => "",
+            None => "?",
+        };
+
+        format!(
+            "{:<20} {:<24} {} {}:{:<8} {:<8} {}",
+            self.path.display(),
+            access,
+            mode_string(self.mode),
+            self.owner,
+            self.group,
+            controller,
+            self.name.as_deref().unwrap_or("?"),
+        )
+    }
+}
+
+fn hints(nodes: &[NodeReport], dir_error: Option<&io::Error>, gids: &[u32]) -> Vec<String> {
+    let mut hints = Vec::new();
+
+    if let Some(err) = dir_error {
+        hints.push(format!("{} can not be listed ({}), is ctl1 running in a container?", INPUT_DIR, err));
+        return hints;
+    }
+    if nodes.is_empty() {
+        hints.push(format!("{} has no event nodes, is ctl1 running in a container?", INPUT_DIR));
+        return hints;
+    }
+
+    // What a node is can only be told once it is opened, so any node that
+    // could not be opened may be the missing controller.
+    let controllers = nodes.iter().filter(|node| node.controller == Some(true)).collect::<Vec<_>>();
+    let denied = nodes.iter().filter(|node| node.denied()).collect::<Vec<_>>();
+    if controllers.is_empty() && denied.is_empty() {
+        hints.push("Nothing looks like a game controller, plug one in, or try --all-devices.".to_string());
+    }
+
+    if !denied.is_empty() {
+        hints.push(format!(
+            "{} input devices can not be opened, if your controller is one of them:",
+            denied.len(),
+        ));
+        // Joining the group only helps if the group may read or write, and
+        // root is no group to join.
+        let mut missing = denied
+            .iter()
+            .filter(|node| node.mode & 0o060 != 0 && node.gid != 0 && !gids.contains(&node.gid))
+            .map(|node| node.group.clone())
+            .collect::<Vec<_>>();
+        missing.sort();
+        missing.dedup();
+
+        for group in missing.iter() {
+            hints.push(format!(
+                "They are accessible to group {}, which you are not in: \
+                 sudo usermod -aG {} $USER, then log in again.",
+                group,
+                group,
+            ));
+        }
+        hints.push(format!(
+            "{} the logged in user access with a udev rule in {}, then \
+             sudo udevadm control --reload && sudo udevadm trigger:",
+            if missing.is_empty() { "Give" } else { "Or give" },
+            UDEV_RULE_PATH,
+        ));
+        hints.push(format!("  {}", UDEV_RULE));
+    }
+
+    if controllers.iter().any(|node| node.access == Ok(DeviceAccess::ReadOnly)) {
+        hints.push("Some controllers are read-only, rumble needs write access.".to_string());
+    }
+    if nodes.iter().any(|node| node.access == Err(EBUSY)) {
+        hints.push("Some input devices are busy, another program may have grabbed them.".to_string());
+    }
+
+    hints
+}
+
+fn event_number(path: &Path) -> u32 {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.trim_start_matches("event").parse().ok())
+        .unwrap_or(u32::MAX)
+}
+
+fn is_permission(errno: i32) -> bool {
+    io::Error::from_raw_os_error(errno).kind() == io::ErrorKind::PermissionDenied
+}
+
+fn errno_name(errno: i32) -> String {
+    match errno {
+        1 => "EPERM".to_string(),
+        2 => "ENOENT".to_string(),
+        5 => "EIO".to_string(),
+        6 => "ENXIO".to_string(),
+        13 => "EACCES".to_string(),
+        EBUSY => "EBUSY".to_string(),
+        19 => "ENODEV".to_string(),
+        errno => format!("errno {}", errno),
+    }
+}
+
+fn mode_string(mode: u32) -> String {
+    let kind = match mode & 0o170000 {
+        0o020000 => 'c',
+        0o040000 => 'd',
+        _ => '-',
+    };
+    let bits = (0..9)
+        .map(|i| match mode & (0o400 >> i) != 0 {
+            true => ['r', 'w', 'x'][i % 3],
+            false => '-',
+        })
+        .collect::<String>();
+
+    format!("{}{}", kind, bits)
+}
+
+/// Effective and supplementary group ids of this process.
+fn user_gids() -> Vec<u32> {
+    let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+    let field = |name: &str| -> Vec<u32> {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|id| id.parse().ok())
+            .collect()
+    };
+
+    let mut gids = field("Gid:").into_iter().skip(1).take(1).collect::<Vec<_>>();
+    for gid in field("Groups:") {
+        if !gids.contains(&gid) {
+            gids.push(gid);
+        }
+    }
+    gids
+}
+
+/// Names by id, from `/etc/group` or `/etc/passwd`.
+fn id_names(path: &str) -> HashMap<u32, String> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,20 @@
+//! Input devices can only be diagnosed on Linux, elsewhere the report only
+//! says so.
+
+#[derive(Debug)]
+pub struct Diagnosis {
+    pub hints: Vec<String>,
+}
+
+impl Diagnosis {
+    pub fn run() -> Self {
+        Self {
+            hints: vec!["Input device diagnostics are only available on Linux.".to_string()],
+        }
+    }
+
+    /// The report, as printed by `ctl1 doctor`.
+    pub fn lines(&self) -> Vec<String> {
+        self.hints.iter().map(|hint| format!("- {}", hint)).collect()
+    }
+}
@@ -2,40 +2,67 @@ extern crate core;
 
 use std::io::stdout;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use tui::backend::CrosstermBackend;
 use tui::Terminal;
 
 use crate::app::{AppReturn, AppState, AppTui};
+use crate::ui::action::UiActions;
 use crate::ui::draw::draw;
 use crate::ui::events::{UiEvents, UiInputEvent};
+use crate::ui::terminal::{shutdown_signal, TerminalGuard};
 
 pub mod app;
+pub mod cli;
+pub mod config;
+#[cfg(target_os = "linux")]
+pub mod doctor;
+#[cfg(not(target_os = "linux"))]
+#[path = "doctor_stub.rs"]
+pub mod doctor;
+pub mod monitor;
 pub mod ui;
 pub mod stick;
 
-pub async fn start_ui(ui_state: Arc<Mutex<AppState>>) -> eyre::Result<()> {
-    let mut app_tui = AppTui::default();
+pub async fn start_ui(
+    ui_state: Arc<Mutex<AppState>>,
+    tick_rate: Duration,
+    actions: UiActions,
+) -> eyre::Result<()> {
+    let mut app_tui = AppTui::new(actions);
 
     let stdout = stdout();
 
-    crossterm::terminal::enable_raw_mode()?;
+    let _guard = TerminalGuard::enter()?;
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
     terminal.hide_cursor()?;
 
-    let tick_rate = Duration::from_millis(40);
     let mut events = UiEvents::new(tick_rate);
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
 
     loop {
-        terminal.draw(|rect| draw(rect, &app_tui, &ui_state))?;
+        let forgotten = ui_state.lock().unwrap().tick(Instant::now());
+        app_tui.forget(&forgotten);
+        terminal.draw(|rect| draw(rect, &mut app_tui, &ui_state))?;
 
-        let result = match events.next().await {
-            UiInputEvent::Input(key) => app_tui.do_action(key).await,
-            UiInputEvent::Tick => AppReturn::Continue,
+        let event = tokio::select! {
+            event = events.next() => event,
+            _ = &mut shutdown => {
+                events.close();
+                break;
+            }
+        };
+
+        let result = match event {
+            Some(UiInputEvent::Input(key)) => app_tui.do_action(key, &ui_state).await,
+            Some(UiInputEvent::Mouse(mouse)) => app_tui.do_mouse(mouse, &ui_state).await,
+            Some(UiInputEvent::Tick) => AppReturn::Continue,
+            None => eyre::bail!("terminal input closed"),
         };
 
         if result == AppReturn::Exit {
@@ -46,7 +73,6 @@ pub async fn start_ui(ui_state: Arc<Mutex<AppState>>) -> eyre::Result<()> {
 
     terminal.clear()?;
     terminal.show_cursor()?;
-    crossterm::terminal::disable_raw_mode()?;
 
     Ok(())
 }
@@ -1,43 +1,163 @@
-use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use clap::Parser;
+use log::LevelFilter;
 use ctl1::app::AppState;
+use ctl1::cli::{Cli, Command};
+use ctl1::config::Config;
+use ctl1::doctor::Diagnosis;
+use ctl1::monitor::{MonitorOptions, start_monitor};
 use ctl1::start_ui;
-use ctl1::stick::read_ctls;
+use ctl1::stick::{axis_name, list_ctls, StickSource};
+use ctl1::stick::replay::ReplaySource;
+use ctl1::stick::source::{CtlSource, read_source};
+use ctl1::stick::trace::{default_trace_path, load_trace, TraceRecorder};
 
-fn init_log() {
-    let level_filter = if let Ok(level) = std::env::var("CTL1_LOG") {
-        log::LevelFilter::from_str(&level).unwrap_or(log::LevelFilter::Info)
-    } else {
-        log::LevelFilter::Info
+fn init_log(cli: &Cli) -> eyre::Result<()> {
+    // A bad --log-level is refused by clap, a bad CTL1_LOG only warned about.
+    let mut unknown = None;
+    let level = match (cli.log_level, std::env::var("CTL1_LOG")) {
+        (Some(level), _) => level,
+        (None, Ok(env_level)) => match env_level.parse() {
+            Ok(level) => level,
+            Err(_) => {
+                unknown = Some(env_level);
+                LevelFilter::Info
+            }
+        },
+        (None, Err(_)) => LevelFilter::Info,
     };
+    tui_logger::init_logger(level).map_err(|err| eyre::eyre!("{}", err))?;
+    tui_logger::set_default_level(level);
+    if let Some(log_file) = &cli.log_file {
+        tui_logger::set_log_file(&log_file.to_string_lossy())?;
+    }
+    if let Some(env_level) = unknown {
+        log::warn!("Unknown log level {} in CTL1_LOG, using {}", env_level, level);
+    }
 
-    tui_logger::init_logger(level_filter).unwrap();
-    tui_logger::set_default_level(log::LevelFilter::Info);
+    Ok(())
 }
 
-#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
-async fn main() -> eyre::Result<()> {
-    init_log();
-
-    let (mut ctl_rx, _) = read_ctls();
+async fn tui(cli: &Cli, source: Box<dyn CtlSource>, mut app_state: AppState) -> eyre::Result<()> {
+    let actions = Config::load_or_default(cli.config.as_deref())?.actions()?;
+    app_state.forget_after = cli.forget_after;
+    let (mut ctl_rx, _) = read_source(source);
 
-    let app_state = Arc::new(Mutex::new(AppState::default()));
+    let app_state = Arc::new(Mutex::new(app_state));
     let ui_app_state = app_state.clone();
+    let ctl_filter = cli.ctl_filter.clone();
 
     tokio::spawn(async move {
         while let Some(ctl_event) = ctl_rx.recv().await {
+            if let Some(filter) = &ctl_filter {
+                if !ctl_event.info().matches(filter) {
+                    continue;
+                }
+            }
+
             let mut app = app_state.lock().unwrap();
-            app.ensure_ctl(ctl_event.ctl_id, ctl_event.ctl_name);
-            let previous = app.current.remove(&ctl_event.ctl_id).unwrap();
-            let current = previous.updated(ctl_event.triggering_event);
-            app.previous.insert(ctl_event.ctl_id, previous);
-            app.current.insert(ctl_event.ctl_id, current);
+            app.pipeline.insert(ctl_event.ctl_id, ctl_rx.stats(ctl_event.ctl_id));
+            app.update(ctl_event);
         }
     });
 
-    start_ui(ui_app_state).await?;
+    let result = start_ui(ui_app_state.clone(), Duration::from_millis(cli.tick_rate), actions).await;
+    // Finish the trace even if the UI failed, it is what the bug report needs.
+    ui_app_state.lock().unwrap().stop_recording();
+    result?;
 
     println!();
     Ok(())
 }
+
+async fn list(cli: &Cli, wait: u64) -> eyre::Result<()> {
+    let ctls = list_ctls(Duration::from_millis(wait), cli.all_devices).await
+        .map_err(|err| eyre::eyre!("can not watch for controllers: {}", err))?;
+
+    for info in ctls.iter() {
+        if let Some(filter) = &cli.ctl_filter {
+            if !info.matches(filter) {
+                continue;
+            }
+        }
+        println!("#{:<3} {:016X} {} {:<20} {}", info.id, info.model, info.kind, info.path, info.name);
+        for axis in info.axes.iter() {
+            println!(
+                "     {:<14} {:>7}..{:<7} fuzz={} flat={} resolution={}",
+                axis_name(axis.event),
+                axis.minimum,
+                axis.maximum,
+                axis.fuzz,
+                axis.flat,
+                axis.resolution,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn doctor() -> eyre::Result<()> {
+    for line in Diagnosis::run().lines() {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+async fn monitor(cli: &Cli, options: &MonitorOptions) -> eyre::Result<()> {
+    let source = StickSource::new(cli.all_devices);
+    let failure = source.failure();
+    let (ctl_rx, _) = read_source(Box::new(source));
+    let ctl_filter = cli.ctl_filter.clone();
+
+    start_monitor(ctl_rx, options, |ctl_event| match &ctl_filter {
+        Some(filter) => ctl_event.info().matches(filter),
+        None => true,
+    }).await?;
+
+    match failure.get() {
+        Some(reason) => Err(eyre::eyre!(reason)),
+        None => Ok(()),
+    }
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+async fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+    init_log(&cli)?;
+
+    match cli.command.clone().unwrap_or(Command::Tui) {
+        Command::Tui => {
+            let source = StickSource::new(cli.all_devices);
+            let app_state = AppState {
+                commands: source.commands(),
+                source_failure: source.failure(),
+                ..AppState::default()
+            };
+            tui(&cli, Box::new(source), app_state).await
+        }
+        Command::List { wait } => list(&cli, wait).await,
+        Command::Monitor(options) => monitor(&cli, &options).await,
+        Command::Doctor => doctor(),
+        Command::Record { path } => {
+            let source = StickSource::new(cli.all_devices);
+            let mut app_state = AppState {
+                commands: source.commands(),
+                source_failure: source.failure(),
+                ..AppState::default()
+            };
+            let path = path.unwrap_or_else(default_trace_path);
+            app_state.start_recording(TraceRecorder::create(path)?);
+            tui(&cli, Box::new(source), app_state).await
+        }
+        Command::Replay { path } => {
+            let mut app_state = AppState::default();
+            let replay = ReplaySource::new(load_trace(path)?);
+            app_state.replay = Some(replay.control());
+            tui(&cli, Box::new(replay), app_state).await
+        }
+    }
+}
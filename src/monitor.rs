@@ -0,0 +1,156 @@
+use std::io::{ErrorKind, Write};
+use std::time::{Duration, Instant};
+
+use clap::{Args, ValueEnum};
+use stick::Event;
+
+use crate::cli::parse_seconds;
+use crate::stick::Ctl1Event;
+use crate::stick::pipeline::CtlReceiver;
+use crate::stick::trace::TraceRecord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MonitorFormat {
+    /// One readable line per event.
+    Text,
+    /// JSON Lines, in the trace file format.
+    Json,
+    /// CSV with a header line.
+    Csv,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MonitorOptions {
+    #[arg(long, value_enum, default_value_t = MonitorFormat::Text)]
+    pub format: MonitorFormat,
+
+    /// Exit once a controller disconnects.
+    #[arg(long)]
+    pub until_disconnect: bool,
+
+    /// Exit after this many events.
+    #[arg(long)]
+    pub count: Option<u64>,
+
+    /// Exit after this many seconds.
+    #[arg(long, value_parser = parse_seconds)]
+    pub seconds: Option<Duration>,
+}
+
+/// Prints the events of `ctl_rx` to stdout until the receiver runs dry or
+/// one of the exit conditions of `options` is met.
+pub async fn start_monitor<F>(mut ctl_rx: CtlReceiver,
+                              options: &MonitorOptions,
+                              filter: F) -> eyre::Result<()>
+    where F: Fn(&Ctl1Event) -> bool {
+    let started = Instant::now();
+    let deadline = options.seconds
+        .map(|seconds| tokio::time::Instant::from_std(started + seconds));
+
+    let mut stdout = std::io::stdout().lock();
+    if options.format == MonitorFormat::Csv {
+        match writeln!(stdout, "t_us,ctl_id,ctl_name,ctl_path,code,value,event") {
+            Err(err) if err.kind() == ErrorKind::BrokenPipe => return Ok(()),
+            result => result?,
+        }
+    }
+
+    let mut printed = 0;
+    while options.count.is_none_or(|count| printed < count) {
+        let ctl_event = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, ctl_rx.recv()).await {
+                Ok(ctl_event) => ctl_event,
+                Err(_) => break,
+            },
+            None => ctl_rx.recv().await,
+        };
+        let ctl_event = match ctl_event {
+            Some(ctl_event) => ctl_event,
+            None => break,
+        };
+        if !filter(&ctl_event) {
+            continue;
+        }
+
+        let line = format_event(options.format, &ctl_event, started)?;
+        match writeln!(stdout, "{}", line) {
+            Err(err) if err.kind() == ErrorKind::BrokenPipe => return Ok(()),
+            result => result?,
+        }
+        printed += 1;
+
+        if options.until_disconnect && matches!(ctl_event.triggering_event, Event::Disconnect) {
+            break;
+        }
+    }
+
+    match stdout.flush() {
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn format_event(format: MonitorFormat,
+                ctl_event: &Ctl1Event,
+                started: Instant) -> eyre::Result<String> {
+    let line = match format {
+        MonitorFormat::Text => {
+            let info = ctl_event.info();
+            format!(
+                "{:>9.3}s {} {}: {}",
+                ctl_event.at.saturating_duration_since(started).as_secs_f64(),
+                info,
+                info.name,
+                ctl_event.triggering_event,
+            )
+        }
+        MonitorFormat::Json => serde_json::to_string(&TraceRecord::new(ctl_event, started))?,
+        MonitorFormat::Csv => {
+            let record = TraceRecord::new(ctl_event, started);
+            format!(
+                "{},{},{},{},{},{},{}",
+                record.t_us,
+                record.ctl_id,
+                csv_field(&record.ctl_name),
+                csv_field(&record.ctl_path),
+                record.code,
+                record.value.map(|value| value.to_string()).unwrap_or_default(),
+                csv_field(&record.event),
+            )
+        }
+    };
+
+    Ok(line)
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("Xbox Wireless Controller"), "Xbox Wireless Controller");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("JoyX(-0.25)"), "JoyX(-0.25)");
+    }
+
+    #[test]
+    fn csv_field_quotes_separators_and_line_breaks() {
+        assert_eq!(csv_field("Pad, Wired"), "\"Pad, Wired\"");
+        assert_eq!(csv_field("Pad\nWired"), "\"Pad\nWired\"");
+        assert_eq!(csv_field("Pad\r"), "\"Pad\r\"");
+    }
+
+    #[test]
+    fn csv_field_doubles_quotes() {
+        assert_eq!(csv_field("8\" Pad"), "\"8\"\" Pad\"");
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::Instant;
+
+/// How the motors are driven over the duration of a rumble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RumblePattern {
+    /// Both motors on throughout.
+    Constant,
+    /// Both motors on, then off, switching every period.
+    Pulse(Duration),
+    /// The left motor, then the right one, switching every period.
+    Alternate(Duration),
+}
+
+/// Something for a controller reader task to do to its controller.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CtlCommand {
+    /// Left and right motor power between 0.0 and 1.0, for `duration`.
+    /// Replaces the rumble in progress, if any.
+    Rumble {
+        left: f32,
+        right: f32,
+        duration: Duration,
+        pattern: RumblePattern,
+    },
+    /// Stops the rumble in progress.
+    StopRumble,
+    /// Starts or stops keeping the raw events the controller reads.
+    KeepRawEvents(bool),
+}
+
+/// A rumble command playing on a controller.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Rumbling {
+    left: f32,
+    right: f32,
+    pattern: RumblePattern,
+    started: Instant,
+    until: Instant,
+}
+
+impl Rumbling {
+    pub(crate) fn new(left: f32, right: f32, duration: Duration, pattern: RumblePattern) -> Self {
+        let started = Instant::now();
+        Self {
+            left: left.clamp(0.0, 1.0),
+            right: right.clamp(0.0, 1.0),
+            pattern,
+            started,
+            until: started + duration,
+        }
+    }
+
+    /// Motor power at `now` and when it changes next, `None` once over.
+    pub(crate) fn at(&self, now: Instant) -> Option<((f32, f32), Instant)> {
+        if now >= self.until {
+            return None;
+        }
+
+        let period = match self.pattern {
+            RumblePattern::Constant => return Some(((self.left, self.right), self.until)),
+            RumblePattern::Pulse(period) | RumblePattern::Alternate(period) => {
+                period.max(Duration::from_millis(1))
+            }
+        };
+
+        let phase = (now - self.started).as_nanos() / period.as_nanos();
+        let next = self.started + period * (phase as u32 + 1);
+        let power = match (self.pattern, phase.is_multiple_of(2)) {
+            (RumblePattern::Alternate(_), true) => (self.left, 0.0),
+            (RumblePattern::Alternate(_), false) => (0.0, self.right),
+            (_, true) => (self.left, self.right),
+            (_, false) => (0.0, 0.0),
+        };
+
+        Some((power, next.min(self.until)))
+    }
+}
+
+/// Command channels of the connected controllers, by controller id, and
+/// which controllers should keep their raw events, connected or not.
+#[derive(Debug, Clone, Default)]
+pub struct CtlCommands {
+    channels: Arc<Mutex<HashMap<u64, UnboundedSender<CtlCommand>>>>,
+    raw_events: Arc<Mutex<HashSet<u64>>>,
+}
+
+impl CtlCommands {
+    pub(crate) fn register(&self, id: u64) -> UnboundedReceiver<CtlCommand> {
+        let (tx, rx) = unbounded_channel();
+        self.channels.lock().unwrap().insert(id, tx);
+        rx
+    }
+
+    pub(crate) fn unregister(&self, id: u64) {
+        self.channels.lock().unwrap().remove(&id);
+    }
+
+    pub fn accepts(&self, id: u64) -> bool {
+        self.channels.lock().unwrap().contains_key(&id)
+    }
+
+    /// Returns `false` if the controller is gone, or never took commands
+    /// (e.g. a replayed one).
+    pub fn send(&self, id: u64, command: CtlCommand) -> bool {
+        match self.channels.lock().unwrap().get(&id) {
+            Some(tx) => tx.send(command).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Starts or stops keeping the raw events of controller `id`, now if it
+    /// is connected and whenever it connects again.
+    pub fn keep_raw_events(&self, id: u64, keep: bool) {
+        let changed = match keep {
+            true => self.raw_events.lock().unwrap().insert(id),
+            false => self.raw_events.lock().unwrap().remove(&id),
+        };
+        if changed {
+            self.send(id, CtlCommand::KeepRawEvents(keep));
+        }
+    }
+
+    pub(crate) fn keeps_raw_events(&self, id: u64) -> bool {
+        self.raw_events.lock().unwrap().contains(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn constant_rumble_holds_until_the_end() {
+        let rumbling = Rumbling::new(0.5, 1.5, ms(100), RumblePattern::Constant);
+        let started = rumbling.started;
+
+        assert_eq!(rumbling.at(started), Some(((0.5, 1.0), started + ms(100))));
+        assert_eq!(rumbling.at(started + ms(99)), Some(((0.5, 1.0), started + ms(100))));
+        assert_eq!(rumbling.at(started + ms(100)), None);
+    }
+
+    #[test]
+    fn pulse_switches_both_motors_every_period() {
+        let rumbling = Rumbling::new(0.5, 0.25, ms(100), RumblePattern::Pulse(ms(30)));
+        let started = rumbling.started;
+
+        assert_eq!(rumbling.at(started), Some(((0.5, 0.25), started + ms(30))));
+        assert_eq!(rumbling.at(started + ms(45)), Some(((0.0, 0.0), started + ms(60))));
+        assert_eq!(rumbling.at(started + ms(60)), Some(((0.5, 0.25), started + ms(90))));
+        // The last period is cut short by the end of the rumble.
+        assert_eq!(rumbling.at(started + ms(95)), Some(((0.0, 0.0), started + ms(100))));
+        assert_eq!(rumbling.at(started + ms(120)), None);
+    }
+
+    #[test]
+    fn alternate_switches_between_motors_every_period() {
+        let rumbling = Rumbling::new(0.5, 0.25, ms(100), RumblePattern::Alternate(ms(50)));
+        let started = rumbling.started;
+
+        assert_eq!(rumbling.at(started + ms(10)), Some(((0.5, 0.0), started + ms(50))));
+        assert_eq!(rumbling.at(started + ms(60)), Some(((0.0, 0.25), started + ms(100))));
+    }
+
+    #[test]
+    fn zero_period_does_not_divide_by_zero() {
+        let rumbling = Rumbling::new(1.0, 1.0, ms(10), RumblePattern::Pulse(Duration::ZERO));
+        let started = rumbling.started;
+
+        assert_eq!(rumbling.at(started), Some(((1.0, 1.0), started + ms(1))));
+    }
+}
@@ -1,28 +1,63 @@
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use log::{debug, info, trace, warn};
-use stick::{Controller, Event, Listener};
-use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::mpsc::error::TrySendError;
+use stick::{AxisInfo, Controller, Event, Listener, RawEvent, Remap};
 use tokio::task::JoinHandle;
 
+use crate::stick::command::{CtlCommand, CtlCommands, Rumbling};
+use crate::stick::pipeline::CtlSender;
+use crate::stick::source::CtlSource;
+
+pub mod command;
+pub mod pipeline;
+pub mod replay;
+pub mod source;
+pub mod trace;
+
 struct ControllersState {
     listener: Listener,
     joiners: Vec<JoinHandle<()>>,
-    tx: Sender<Ctl1Event>,
+    tx: CtlSender,
+    commands: CtlCommands,
+    connections: u64,
+    /// Ids of the disconnected controllers, by `device_key`.
+    released: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+/// What tells `controller` apart from other controllers of the same model
+/// across reconnects: its serial number, else the port it is plugged into.
+/// Not the device node, the kernel hands freed ones out again.
+fn device_key(controller: &Controller) -> Option<String> {
+    let stable = controller.serial().or_else(|| controller.location())?;
+    Some(format!("{:016X}/{}", controller.id(), stable))
 }
 
 impl ControllersState {
-    pub fn new(tx: Sender<Ctl1Event>) -> Self {
-        Self {
-            listener: Listener::default(),
+    pub fn new(tx: CtlSender, commands: CtlCommands, all_devices: bool) -> io::Result<Self> {
+        Ok(Self {
+            listener: Listener::try_with_all_devices(Remap::default(), all_devices)?,
             joiners: Vec::with_capacity(2),
             tx,
-        }
+            commands,
+            connections: 0,
+            released: Arc::default(),
+        })
     }
 
-    pub fn open() -> (Self, Receiver<Ctl1Event>) {
-        let (tx, rx) = tokio::sync::mpsc::channel(1);
-        (Self::new(tx), rx)
+    /// The id the controller with `key` had if it was connected before and
+    /// disconnected since, so its panel and history carry on, else a new
+    /// one. Without a key there is no telling it from another controller of
+    /// the same model, so it gets a new id.
+    fn connection_id(&mut self, key: Option<&str>) -> u64 {
+        let reused = key.and_then(|key| self.released.lock().unwrap().remove(key));
+
+        reused.unwrap_or_else(|| {
+            self.connections += 1;
+            self.connections
+        })
     }
 }
 
@@ -135,6 +170,8 @@ pub struct CtlState {
 impl CtlState {
     pub fn updated(&self, event: Event) -> Self {
         let mut clone = self.clone();
+        // Anything but a disconnect means the controller is (back) online.
+        clone.disconnected = false;
 
         match event {
             Event::Disconnect => clone.disconnected = true,
@@ -247,78 +284,373 @@ impl CtlState {
 }
 
 
+/// Label and value of an analog event, `None` for buttons and `Disconnect`.
+pub fn axis_of(event: &Event) -> Option<(&'static str, f64)> {
+    match *event {
+        Event::TriggerL(v) => Some(("Trigger L", v)),
+        Event::TriggerR(v) => Some(("Trigger R", v)),
+        Event::JoyX(v) => Some(("Joy X", v)),
+        Event::JoyY(v) => Some(("Joy Y", v)),
+        Event::JoyZ(v) => Some(("Joy Z", v)),
+        Event::CamX(v) => Some(("Cam X", v)),
+        Event::CamY(v) => Some(("Cam Y", v)),
+        Event::CamZ(v) => Some(("Cam Z", v)),
+        Event::Slew(v) => Some(("Slew", v)),
+        Event::Throttle(v) => Some(("Throttle", v)),
+        Event::ThrottleL(v) => Some(("Throttle L", v)),
+        Event::ThrottleR(v) => Some(("Throttle R", v)),
+        Event::Volume(v) => Some(("Volume", v)),
+        Event::Wheel(v) => Some(("Wheel", v)),
+        Event::Rudder(v) => Some(("Rudder", v)),
+        Event::Gas(v) => Some(("Gas", v)),
+        Event::Brake(v) => Some(("Brake", v)),
+        Event::MouseX(v) => Some(("Mouse X", v)),
+        Event::MouseY(v) => Some(("Mouse Y", v)),
+        Event::ScrollX(v) => Some(("Scroll X", v)),
+        Event::ScrollY(v) => Some(("Scroll Y", v)),
+        Event::ActionWheelX(v) => Some(("Action Wheel X", v)),
+        Event::ActionWheelY(v) => Some(("Action Wheel Y", v)),
+        _ => None,
+    }
+}
+
+/// Name of the axis producing event `id`, as in [`axis_of`].
+pub fn axis_name(id: u8) -> String {
+    let event = Event::from_id(id, 0.0);
+    match axis_of(&event) {
+        Some((name, _)) => name.to_string(),
+        None => format!("{:?}", event),
+    }
+}
+
+
+/// Identifies one physical controller for as long as it stays connected.
+///
+/// `id` is a per-connection counter, so two identical gamepads (which share
+/// the same `model`, the hardware bus/vendor/product/version id) never
+/// collapse into one. A device that reconnects gets the id it had back, if
+/// it reports a serial number or where it is plugged in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CtlInfo {
+    pub id: u64,
+    pub model: u64,
+    pub kind: char,
+    pub name: String,
+    pub path: String,
+    /// Raw ranges of the analog axes, empty when replaying.
+    pub axes: Arc<[AxisInfo]>,
+    /// Whether it can rumble, false when replaying.
+    pub rumble: bool,
+}
+
+impl CtlInfo {
+    fn new(id: u64, controller: &Controller) -> Self {
+        Self {
+            id,
+            model: controller.id(),
+            kind: controller.kind(),
+            name: controller.name().to_string(),
+            path: controller.path().unwrap_or("?").to_string(),
+            axes: controller.axes().into(),
+            rumble: controller.effects().rumble(),
+        }
+    }
+
+    pub fn is_flightstick(&self) -> bool {
+        self.kind == 'f'
+    }
+
+    /// `#<id>`, or a case insensitive part of the name or path.
+    pub fn matches(&self, filter: &str) -> bool {
+        if let Some(id) = filter.strip_prefix('#') {
+            return id.parse() == Ok(self.id);
+        }
+
+        let filter = filter.to_lowercase();
+        self.name.to_lowercase().contains(&filter) || self.path.to_lowercase().contains(&filter)
+    }
+}
+
+#[cfg(test)]
+impl CtlInfo {
+    /// A gamepad on `/dev/input/event<id>`, for tests.
+    pub(crate) fn fixture(id: u64) -> Self {
+        Self {
+            id,
+            model: 0,
+            kind: 'g',
+            name: format!("pad {}", id),
+            path: format!("/dev/input/event{}", id),
+            axes: Arc::new([]),
+            rumble: false,
+        }
+    }
+}
+
+impl Display for CtlInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.path, self.id)
+    }
+}
+
+
 #[derive(Clone, Debug)]
 pub struct Ctl1Event {
     pub triggering_event: Event,
+    pub at: Instant,
     pub ctl_name: String,
     pub ctl_id: u64,
+    pub ctl_model: u64,
+    pub ctl_kind: char,
+    pub ctl_path: String,
+    pub ctl_axes: Arc<[AxisInfo]>,
+    pub ctl_rumble: bool,
+    /// What the hardware sent, that `triggering_event` was made from.
+    /// Empty unless the controller keeps its raw events, and for replayed
+    /// events.
+    pub raw: Vec<RawEvent>,
 }
 
 impl Ctl1Event {
     pub fn new(event: Event,
-               ctl_name: String,
-               ctl_id: u64, ) -> Self {
+               info: &CtlInfo) -> Self {
         Self {
             triggering_event: event,
-            ctl_name,
-            ctl_id,
+            at: Instant::now(),
+            ctl_name: info.name.clone(),
+            ctl_id: info.id,
+            ctl_model: info.model,
+            ctl_kind: info.kind,
+            ctl_path: info.path.clone(),
+            ctl_axes: info.axes.clone(),
+            ctl_rumble: info.rumble,
+            raw: Vec::new(),
+        }
+    }
+
+    pub fn info(&self) -> CtlInfo {
+        CtlInfo {
+            id: self.ctl_id,
+            model: self.ctl_model,
+            kind: self.ctl_kind,
+            name: self.ctl_name.clone(),
+            path: self.ctl_path.clone(),
+            axes: self.ctl_axes.clone(),
+            rumble: self.ctl_rumble,
         }
     }
 }
 
 
-async fn read_ctl(listener: &mut Listener,
-                  tx: Sender<Ctl1Event>) -> JoinHandle<()> {
+async fn read_ctl(state: &mut ControllersState) -> JoinHandle<()> {
     trace!("waiting controller...");
 
-    let mut controller: Controller = listener.await;
-    info!("connected, id={:016X}, name={}",controller.id(),controller.name());
+    let mut controller: Controller = (&mut state.listener).await;
+    let key = device_key(&controller);
+    let info = CtlInfo::new(state.connection_id(key.as_deref()), &controller);
+    info!("connected, ctl={}, model={:016X}, name={}", info, info.model, info.name);
+
+    let tx = state.tx.clone();
+    let released = state.released.clone();
+    let commands = state.commands.clone();
+    let mut command_rx = commands.register(info.id);
+    controller.keep_raw_events(commands.keeps_raw_events(info.id));
 
     tokio::spawn(async move {
+        let mut rumbling = None;
+        let mut rumble_next = None;
+
         loop {
-            let event = (&mut controller).await;
-            trace!("EVENT {:?}", event);
-            let ctl_event = Ctl1Event::new(
-                event,
-                controller.name().to_string(),
-                controller.id(),
-            );
-
-            if let Err(err) = tx.try_send(ctl_event) {
-                match err {
-                    TrySendError::Full(_) => {}
-                    TrySendError::Closed(_) => {
-                        debug!(
-                            "receiver closed for id={:016X}, name={}",
-                            controller.id(),
-                            controller.name(),
-                        );
-                        return;
+            let event = tokio::select! {
+                event = &mut controller => event,
+                Some(command) = command_rx.recv() => {
+                    trace!("COMMAND {:?} ctl={}", command, info);
+                    match command {
+                        CtlCommand::Rumble { left, right, duration, pattern } => {
+                            rumbling = Some(Rumbling::new(left, right, duration, pattern));
+                        }
+                        CtlCommand::StopRumble => {
+                            rumbling = None;
+                            controller.rumble(0.0);
+                        }
+                        CtlCommand::KeepRawEvents(keep) => {
+                            controller.keep_raw_events(keep);
+                            continue;
+                        }
                     }
+                    rumble_next = apply_rumble(&mut controller, &mut rumbling);
+                    continue;
+                }
+                _ = sleep_until_some(rumble_next) => {
+                    rumble_next = apply_rumble(&mut controller, &mut rumbling);
+                    continue;
                 }
+            };
+            trace!("EVENT {:?}", event);
+            let mut ctl_event = Ctl1Event::new(event, &info);
+            ctl_event.raw = controller.raw_events();
+
+            // Taking commands tells the UI the controller is connected, so
+            // stop before the disconnect shows.
+            if let Event::Disconnect = &event {
+                commands.unregister(info.id);
+            }
+
+            if tx.send(ctl_event).is_err() {
+                debug!(
+                    "receiver closed for ctl={}, name={}",
+                    info,
+                    info.name,
+                );
+                commands.unregister(info.id);
+                return;
             }
 
             if let Event::Disconnect = &event {
                 info!(
-                    "controller disconnected id={:016X}, name={}",
-                    controller.id(),
-                    controller.name(),
+                    "controller disconnected ctl={}, name={}",
+                    info,
+                    info.name,
                 );
+                if let Some(key) = key {
+                    released.lock().unwrap().insert(key, info.id);
+                }
                 return;
             }
         }
     })
 }
 
-pub fn read_ctls() -> (Receiver<Ctl1Event>, JoinHandle<()>) {
-    let (mut state, rx) = ControllersState::open();
+/// Sets the motors to what `rumbling` says for now, returns when to do it
+/// again.
+fn apply_rumble(controller: &mut Controller, rumbling: &mut Option<Rumbling>) -> Option<tokio::time::Instant> {
+    let now = tokio::time::Instant::now();
+    match rumbling.as_ref().and_then(|rumbling| rumbling.at(now)) {
+        Some((power, next)) => {
+            controller.rumble(power);
+            Some(next)
+        }
+        None => {
+            if rumbling.take().is_some() {
+                controller.rumble(0.0);
+            }
+            None
+        }
+    }
+}
 
-    let handle = tokio::spawn(async move {
-        loop {
-            let joiner = read_ctl(&mut state.listener, state.tx.clone()).await;
-            state.joiners.push(joiner)
+async fn sleep_until_some(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Why a [`StickSource`] could not watch for controllers, if it could not.
+#[derive(Debug, Clone, Default)]
+pub struct SourceFailure(Arc<Mutex<Option<String>>>);
+
+impl SourceFailure {
+    fn set(&self, reason: String) {
+        *self.0.lock().unwrap() = Some(reason);
+    }
+
+    pub fn get(&self) -> Option<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// Controllers connected to this machine, through the `stick` crate.
+#[derive(Debug, Clone, Default)]
+pub struct StickSource {
+    commands: CtlCommands,
+    failure: SourceFailure,
+    all_devices: bool,
+}
+
+impl StickSource {
+    /// With `all_devices`, input devices that do not look like game
+    /// controllers (keyboards, power buttons...) are connected too.
+    pub fn new(all_devices: bool) -> Self {
+        Self {
+            commands: CtlCommands::default(),
+            failure: SourceFailure::default(),
+            all_devices,
         }
-    });
+    }
+
+    /// Command channels of the controllers this source connects.
+    pub fn commands(&self) -> CtlCommands {
+        self.commands.clone()
+    }
+
+    /// Set once the source started, if it could not watch for controllers.
+    pub fn failure(&self) -> SourceFailure {
+        self.failure.clone()
+    }
+}
+
+impl CtlSource for StickSource {
+    fn start(self: Box<Self>, tx: CtlSender) -> JoinHandle<()> {
+        let mut state = match ControllersState::new(tx, self.commands, self.all_devices) {
+            Ok(state) => state,
+            Err(err) => {
+                // Dropping `tx` ends the events, there will be none.
+                let reason = format!("can not watch for controllers: {}", err);
+                warn!("{}", reason);
+                self.failure.set(reason);
+                return tokio::spawn(async {});
+            }
+        };
+
+        tokio::spawn(async move {
+            loop {
+                let joiner = read_ctl(&mut state).await;
+                state.joiners.push(joiner)
+            }
+        })
+    }
+}
 
-    (rx, handle)
+/// Controllers connecting within `wait`, the already connected ones show up
+/// right away.
+pub async fn list_ctls(wait: Duration, all_devices: bool) -> io::Result<Vec<CtlInfo>> {
+    let mut listener = Listener::try_with_all_devices(Remap::default(), all_devices)?;
+    let deadline = tokio::time::Instant::now() + wait;
+
+    let mut ctls = Vec::with_capacity(2);
+    while let Ok(controller) = tokio::time::timeout_at(deadline, &mut listener).await {
+        ctls.push(CtlInfo::new(ctls.len() as u64 + 1, &controller));
+    }
+    Ok(ctls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info() -> CtlInfo {
+        CtlInfo {
+            name: "Xbox Wireless Controller".to_string(),
+            path: "/dev/input/event5".to_string(),
+            ..CtlInfo::fixture(12)
+        }
+    }
+
+    #[test]
+    fn matches_the_id() {
+        assert!(info().matches("#12"));
+        assert!(!info().matches("#1"));
+        assert!(!info().matches("#x"));
+        // Without `#` a number is looked for in the name and path.
+        assert!(info().matches("5"));
+    }
+
+    #[test]
+    fn matches_part_of_the_name_or_path_ignoring_case() {
+        assert!(info().matches("xbox"));
+        assert!(info().matches("WIRELESS"));
+        assert!(info().matches("event5"));
+        assert!(!info().matches("dualsense"));
+    }
 }
@@ -0,0 +1,264 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem::discriminant;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use stick::Event;
+use tokio::sync::Notify;
+
+use crate::stick::{axis_of, Ctl1Event};
+
+pub const PIPELINE_CAPACITY: usize = 1024;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CtlPipelineStats {
+    pub delivered: u64,
+    pub coalesced: u64,
+    pub dropped: u64,
+}
+
+struct Shared {
+    capacity: usize,
+    queue: Mutex<VecDeque<Ctl1Event>>,
+    stats: Mutex<HashMap<u64, CtlPipelineStats>>,
+    notify: Notify,
+    senders: AtomicUsize,
+    closed: AtomicBool,
+}
+
+/// Creates the queue between controller readers and the consumer.
+///
+/// Edge events (buttons, `Disconnect`) are always queued. A new axis sample
+/// replaces the last queued event of the same controller if that is a
+/// sample of the same axis, so events keep their order across axes. Axis
+/// samples that can be neither queued nor coalesced are dropped once
+/// `capacity` is reached.
+pub fn pipeline(capacity: usize) -> (CtlSender, CtlReceiver) {
+    let shared = Arc::new(Shared {
+        capacity,
+        queue: Mutex::new(VecDeque::with_capacity(capacity)),
+        stats: Mutex::new(HashMap::with_capacity(2)),
+        notify: Notify::new(),
+        senders: AtomicUsize::new(1),
+        closed: AtomicBool::new(false),
+    });
+
+    (CtlSender(shared.clone()), CtlReceiver(shared))
+}
+
+/// The receiver is gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Closed;
+
+pub struct CtlSender(Arc<Shared>);
+
+impl CtlSender {
+    /// Queues the event, fails if the receiver is gone.
+    pub fn send(&self, ctl_event: Ctl1Event) -> Result<(), Closed> {
+        if self.0.closed.load(Ordering::Acquire) {
+            return Err(Closed);
+        }
+
+        let mut queue = self.0.queue.lock().unwrap();
+
+        if is_axis(&ctl_event.triggering_event) {
+            if coalesce(&mut queue, &ctl_event) {
+                self.0.stats.lock().unwrap()
+                    .entry(ctl_event.ctl_id)
+                    .or_default()
+                    .coalesced += 1;
+                return Ok(());
+            }
+            if queue.len() >= self.0.capacity {
+                self.0.stats.lock().unwrap()
+                    .entry(ctl_event.ctl_id)
+                    .or_default()
+                    .dropped += 1;
+                return Ok(());
+            }
+        }
+
+        queue.push_back(ctl_event);
+        drop(queue);
+        self.0.notify.notify_one();
+
+        Ok(())
+    }
+}
+
+impl Clone for CtlSender {
+    fn clone(&self) -> Self {
+        self.0.senders.fetch_add(1, Ordering::AcqRel);
+        Self(self.0.clone())
+    }
+}
+
+impl Drop for CtlSender {
+    fn drop(&mut self) {
+        if self.0.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.notify.notify_one();
+        }
+    }
+}
+
+pub struct CtlReceiver(Arc<Shared>);
+
+impl CtlReceiver {
+    /// Next event, or `None` once every sender is dropped and the queue is
+    /// drained.
+    pub async fn recv(&mut self) -> Option<Ctl1Event> {
+        loop {
+            if let Some(ctl_event) = self.0.queue.lock().unwrap().pop_front() {
+                self.0.stats.lock().unwrap()
+                    .entry(ctl_event.ctl_id)
+                    .or_default()
+                    .delivered += 1;
+                return Some(ctl_event);
+            }
+
+            if self.0.senders.load(Ordering::Acquire) == 0 {
+                return None;
+            }
+
+            self.0.notify.notified().await;
+        }
+    }
+
+    pub fn stats(&self, ctl_id: u64) -> CtlPipelineStats {
+        self.0.stats.lock().unwrap()
+            .get(&ctl_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for CtlReceiver {
+    fn drop(&mut self) {
+        self.0.closed.store(true, Ordering::Release);
+    }
+}
+
+fn coalesce(queue: &mut VecDeque<Ctl1Event>, ctl_event: &Ctl1Event) -> bool {
+    let last = queue
+        .iter_mut()
+        .rev()
+        .find(|queued| queued.ctl_id == ctl_event.ctl_id);
+
+    match last {
+        Some(queued) if discriminant(&queued.triggering_event) == discriminant(&ctl_event.triggering_event) => {
+            queued.triggering_event = ctl_event.triggering_event;
+            queued.at = ctl_event.at;
+            // Both were read, keep what the hardware sent for either.
+            queued.raw.extend_from_slice(&ctl_event.raw);
+            true
+        }
+        _ => false,
+    }
+}
+
+pub fn is_axis(event: &Event) -> bool {
+    axis_of(event).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use stick::RawEvent;
+
+    use super::*;
+    use crate::stick::CtlInfo;
+
+    fn ctl_event(id: u64, event: Event) -> Ctl1Event {
+        Ctl1Event::new(event, &CtlInfo::fixture(id))
+    }
+
+    fn raw(value: i32) -> RawEvent {
+        RawEvent { kind: 0x03, code: 0x00, value, time: Duration::ZERO }
+    }
+
+    fn queued(events: Vec<Ctl1Event>) -> VecDeque<Ctl1Event> {
+        events.into_iter().collect()
+    }
+
+    #[test]
+    fn coalesce_replaces_the_same_axis() {
+        let mut queue = queued(vec![ctl_event(1, Event::JoyX(0.1))]);
+        let mut next = ctl_event(1, Event::JoyX(0.2));
+        next.raw = vec![raw(2)];
+
+        assert!(coalesce(&mut queue, &next));
+        assert_eq!(queue.len(), 1);
+        assert!(matches!(queue[0].triggering_event, Event::JoyX(v) if v == 0.2));
+        assert_eq!(queue[0].at, next.at);
+        assert_eq!(queue[0].raw, vec![raw(2)]);
+    }
+
+    #[test]
+    fn coalesce_keeps_raw_events_of_both() {
+        let mut first = ctl_event(1, Event::JoyX(0.1));
+        first.raw = vec![raw(1)];
+        let mut queue = queued(vec![first]);
+        let mut next = ctl_event(1, Event::JoyX(0.2));
+        next.raw = vec![raw(2)];
+
+        assert!(coalesce(&mut queue, &next));
+        assert_eq!(queue[0].raw, vec![raw(1), raw(2)]);
+    }
+
+    #[test]
+    fn coalesce_only_into_the_last_event_of_the_controller() {
+        // Another axis came in between, replacing the first sample would
+        // reorder them.
+        let mut queue = queued(vec![
+            ctl_event(1, Event::JoyX(0.1)),
+            ctl_event(1, Event::JoyY(0.1)),
+        ]);
+        assert!(!coalesce(&mut queue, &ctl_event(1, Event::JoyX(0.2))));
+
+        // Other controllers don't count.
+        let mut queue = queued(vec![
+            ctl_event(1, Event::JoyX(0.1)),
+            ctl_event(2, Event::JoyY(0.1)),
+        ]);
+        assert!(coalesce(&mut queue, &ctl_event(1, Event::JoyX(0.2))));
+        assert!(matches!(queue[0].triggering_event, Event::JoyX(v) if v == 0.2));
+        assert!(matches!(queue[1].triggering_event, Event::JoyY(v) if v == 0.1));
+    }
+
+    #[test]
+    fn coalesce_needs_a_queued_event_of_the_controller() {
+        let mut queue = queued(vec![ctl_event(2, Event::JoyX(0.1))]);
+        assert!(!coalesce(&mut queue, &ctl_event(1, Event::JoyX(0.2))));
+        assert!(!coalesce(&mut VecDeque::new(), &ctl_event(1, Event::JoyX(0.2))));
+    }
+
+    #[tokio::test]
+    async fn send_drops_axis_samples_past_capacity_but_not_edges() {
+        let (tx, mut rx) = pipeline(2);
+        tx.send(ctl_event(1, Event::ActionA(true))).unwrap();
+        tx.send(ctl_event(1, Event::JoyX(0.1))).unwrap();
+        tx.send(ctl_event(1, Event::JoyX(0.2))).unwrap();
+        tx.send(ctl_event(1, Event::ActionA(false))).unwrap();
+        tx.send(ctl_event(1, Event::JoyX(0.3))).unwrap();
+        drop(tx);
+
+        let mut events = Vec::new();
+        while let Some(ctl_event) = rx.recv().await {
+            events.push(ctl_event.triggering_event);
+        }
+
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], Event::ActionA(true)));
+        assert!(matches!(events[1], Event::JoyX(v) if v == 0.2));
+        assert!(matches!(events[2], Event::ActionA(false)));
+        assert_eq!(rx.stats(1), CtlPipelineStats { delivered: 3, coalesced: 1, dropped: 1 });
+    }
+
+    #[test]
+    fn send_fails_once_the_receiver_is_gone() {
+        let (tx, rx) = pipeline(1);
+        drop(rx);
+        assert_eq!(tx.send(ctl_event(1, Event::ActionA(true))), Err(Closed));
+    }
+}
@@ -0,0 +1,248 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{debug, info};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use crate::stick::Ctl1Event;
+use crate::stick::pipeline::CtlSender;
+use crate::stick::source::CtlSource;
+use crate::stick::trace::TraceRecord;
+
+static SPEEDS: [f64; 9] = [0.1, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0, 64.0];
+const DEFAULT_SPEED: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayStatus {
+    pub speed: f64,
+    pub paused: bool,
+    pub position: usize,
+    pub total: usize,
+    pub finished: bool,
+}
+
+#[derive(Debug)]
+struct ReplayState {
+    speed: usize,
+    paused: bool,
+    steps: usize,
+    position: usize,
+    total: usize,
+    finished: bool,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<ReplayState>,
+    notify: Notify,
+}
+
+/// Playback controls of a running replay, shared between the replay task and
+/// the UI.
+#[derive(Debug, Clone)]
+pub struct ReplayControl(Arc<Shared>);
+
+impl ReplayControl {
+    fn new(total: usize) -> Self {
+        Self(Arc::new(Shared {
+            state: Mutex::new(ReplayState {
+                speed: DEFAULT_SPEED,
+                paused: false,
+                steps: 0,
+                position: 0,
+                total,
+                finished: false,
+            }),
+            notify: Notify::new(),
+        }))
+    }
+
+    pub fn status(&self) -> ReplayStatus {
+        let state = self.0.state.lock().unwrap();
+        ReplayStatus {
+            speed: SPEEDS[state.speed],
+            paused: state.paused,
+            position: state.position,
+            total: state.total,
+            finished: state.finished,
+        }
+    }
+
+    pub fn toggle_pause(&self) {
+        self.update(|state| {
+            state.paused = !state.paused;
+            state.steps = 0;
+        });
+    }
+
+    /// Pauses the replay, or releases the next event if already paused.
+    pub fn step(&self) {
+        self.update(|state| match state.paused {
+            true => state.steps += 1,
+            false => state.paused = true,
+        });
+    }
+
+    pub fn faster(&self) {
+        self.update(|state| state.speed = (state.speed + 1).min(SPEEDS.len() - 1));
+    }
+
+    pub fn slower(&self) {
+        self.update(|state| state.speed = state.speed.saturating_sub(1));
+    }
+
+    fn update<F>(&self, f: F)
+        where F: FnOnce(&mut ReplayState) {
+        f(&mut self.0.state.lock().unwrap());
+        self.0.notify.notify_one();
+    }
+
+    /// Waits until an event `delay` (in trace time) after the previous one
+    /// is due, following speed changes, pauses and steps made meanwhile.
+    async fn wait(&self, delay: Duration) {
+        let mut remaining = delay;
+
+        loop {
+            let speed = {
+                let mut state = self.0.state.lock().unwrap();
+                if state.paused {
+                    if state.steps > 0 {
+                        state.steps -= 1;
+                        return;
+                    }
+                    None
+                } else {
+                    Some(SPEEDS[state.speed])
+                }
+            };
+
+            let speed = match speed {
+                Some(speed) => speed,
+                None => {
+                    self.0.notify.notified().await;
+                    continue;
+                }
+            };
+
+            if remaining.is_zero() {
+                return;
+            }
+
+            let started = Instant::now();
+            tokio::select! {
+                _ = tokio::time::sleep(remaining.div_f64(speed)) => return,
+                _ = self.0.notify.notified() => {
+                    remaining = remaining.saturating_sub(started.elapsed().mul_f64(speed));
+                }
+            }
+        }
+    }
+
+    fn advance(&self) {
+        self.0.state.lock().unwrap().position += 1;
+    }
+
+    fn finish(&self) {
+        self.update(|state| state.finished = true);
+    }
+}
+
+
+/// Plays recorded events back, stamped with the time they are replayed at.
+#[derive(Debug)]
+pub struct ReplaySource {
+    records: Vec<TraceRecord>,
+    control: ReplayControl,
+}
+
+impl ReplaySource {
+    pub fn new(records: Vec<TraceRecord>) -> Self {
+        let control = ReplayControl::new(records.len());
+        Self {
+            records,
+            control,
+        }
+    }
+
+    pub fn control(&self) -> ReplayControl {
+        self.control.clone()
+    }
+}
+
+impl CtlSource for ReplaySource {
+    fn start(self: Box<Self>, tx: CtlSender) -> JoinHandle<()> {
+        let Self { records, control } = *self;
+
+        tokio::spawn(async move {
+            let mut previous_us = records.first().map(|record| record.t_us).unwrap_or_default();
+
+            for record in records {
+                let delay = Duration::from_micros(record.t_us.saturating_sub(previous_us));
+                previous_us = record.t_us;
+                control.wait(delay).await;
+
+                let ctl_event = Ctl1Event::new(record.triggering_event(), &record.info());
+                if tx.send(ctl_event).is_err() {
+                    debug!("receiver closed, replay stopped");
+                    return;
+                }
+                control.advance();
+            }
+
+            info!("replay finished");
+            control.finish();
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stick::Event;
+
+    use super::*;
+    use crate::stick::CtlInfo;
+    use crate::stick::source::read_source;
+    use crate::stick::trace::{load_trace, TraceRecorder};
+
+    #[tokio::test]
+    async fn replays_a_recorded_trace() {
+        // A name that needs escaping in JSON.
+        let info = CtlInfo {
+            name: "Pad \"Pro\", wired".to_string(),
+            ..CtlInfo::fixture(3)
+        };
+        let events = [Event::ActionA(true), Event::JoyX(-0.25), Event::ActionA(false), Event::Disconnect];
+
+        let path = std::env::temp_dir().join(format!("ctl1-replay-test-{}.jsonl", std::process::id()));
+        let mut recorder = TraceRecorder::create(&path).unwrap();
+        for event in events {
+            recorder.record(&Ctl1Event::new(event, &info)).unwrap();
+        }
+        recorder.finish().unwrap();
+        let records = load_trace(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let source = ReplaySource::new(records);
+        let control = source.control();
+        let (mut rx, handle) = read_source(Box::new(source));
+        let mut replayed = Vec::new();
+        while let Some(ctl_event) = rx.recv().await {
+            replayed.push(ctl_event);
+        }
+        handle.await.unwrap();
+
+        assert_eq!(replayed.len(), events.len());
+        for (ctl_event, event) in replayed.iter().zip(events) {
+            assert_eq!(ctl_event.info(), info);
+            assert_eq!(format!("{:?}", ctl_event.triggering_event), format!("{:?}", event));
+        }
+        assert_eq!(control.status(), ReplayStatus {
+            speed: 1.0,
+            paused: false,
+            position: events.len(),
+            total: events.len(),
+            finished: true,
+        });
+    }
+}
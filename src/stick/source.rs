@@ -0,0 +1,36 @@
+use tokio::task::JoinHandle;
+
+use crate::stick::Ctl1Event;
+use crate::stick::pipeline::{CtlReceiver, CtlSender, pipeline, PIPELINE_CAPACITY};
+
+/// Anything producing controller events: connected controllers, replayed
+/// traces, fixtures...
+pub trait CtlSource: Send {
+    /// Spawns the task producing events into `tx`. The task ends once the
+    /// source has no more events, or once the receiver is gone.
+    fn start(self: Box<Self>, tx: CtlSender) -> JoinHandle<()>;
+}
+
+/// Starts the source, its events are read from the returned receiver.
+pub fn read_source(source: Box<dyn CtlSource>) -> (CtlReceiver, JoinHandle<()>) {
+    let (tx, rx) = pipeline(PIPELINE_CAPACITY);
+    let handle = source.start(tx);
+    (rx, handle)
+}
+
+
+/// A fixed list of events, all queued at once.
+#[derive(Debug, Clone, Default)]
+pub struct EventsSource(pub Vec<Ctl1Event>);
+
+impl CtlSource for EventsSource {
+    fn start(self: Box<Self>, tx: CtlSender) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            for ctl_event in self.0 {
+                if tx.send(ctl_event).is_err() {
+                    return;
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,186 @@
+//! Controller session traces, stored as [JSON Lines](https://jsonlines.org/).
+//!
+//! Every line is one [`TraceRecord`], one event of one controller, in the
+//! order they were received:
+//!
+//! ```text
+//! {"t_us":1520331,"ctl_id":1,"ctl_name":"Xbox Wireless Controller","ctl_model":844429225427727,"ctl_kind":"x","ctl_path":"/dev/input/event5","code":32,"value":-0.25,"event":"JoyX(-0.25)"}
+//! ```
+//!
+//! - `t_us`: microseconds since the recording started, from a monotonic clock.
+//! - `ctl_id`, `ctl_name`, `ctl_model`, `ctl_kind`, `ctl_path`: the
+//!   controller, as in [`CtlInfo`](crate::stick::CtlInfo).
+//! - `code`, `value`: the event, as in `stick::Event::to_id`. `value` is
+//!   `null` for `Disconnect`.
+//! - `event`: the event for humans to read, ignored when the trace is read
+//!   back.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use stick::Event;
+
+use crate::stick::{Ctl1Event, CtlInfo};
+
+/// Longest a written record stays in memory before it reaches the file.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceRecord {
+    pub t_us: u64,
+    pub ctl_id: u64,
+    pub ctl_name: String,
+    pub ctl_model: u64,
+    pub ctl_kind: char,
+    pub ctl_path: String,
+    pub code: u8,
+    pub value: Option<f64>,
+    #[serde(default)]
+    pub event: String,
+}
+
+impl TraceRecord {
+    pub fn new(ctl_event: &Ctl1Event, started: Instant) -> Self {
+        let (code, value) = ctl_event.triggering_event.to_id();
+
+        Self {
+            t_us: ctl_event.at.saturating_duration_since(started).as_micros() as u64,
+            ctl_id: ctl_event.ctl_id,
+            ctl_name: ctl_event.ctl_name.clone(),
+            ctl_model: ctl_event.ctl_model,
+            ctl_kind: ctl_event.ctl_kind,
+            ctl_path: ctl_event.ctl_path.clone(),
+            code,
+            value: if value.is_nan() { None } else { Some(value) },
+            event: format!("{:?}", ctl_event.triggering_event),
+        }
+    }
+
+    pub fn triggering_event(&self) -> Event {
+        Event::from_id(self.code, self.value.unwrap_or(f64::NAN))
+    }
+
+    pub fn info(&self) -> CtlInfo {
+        CtlInfo {
+            id: self.ctl_id,
+            model: self.ctl_model,
+            kind: self.ctl_kind,
+            name: self.ctl_name.clone(),
+            path: self.ctl_path.clone(),
+            axes: Default::default(),
+            rumble: false,
+        }
+    }
+}
+
+
+/// Appends the events it is given to a trace file.
+///
+/// Records are buffered and flushed at most `FLUSH_INTERVAL` apart, and when
+/// the recorder is finished, so recording does not hit the disk for every
+/// event and a trace still loses at most the last second if ctl1 does not
+/// exit cleanly.
+#[derive(Debug)]
+pub struct TraceRecorder {
+    path: PathBuf,
+    started: Instant,
+    writer: BufWriter<File>,
+    records: u64,
+    /// When the buffer was last flushed, `None` if nothing is buffered.
+    flushed: Option<Instant>,
+}
+
+impl TraceRecorder {
+    pub fn create<P>(path: P) -> eyre::Result<Self>
+        where P: AsRef<Path> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path)
+            .wrap_err_with(|| format!("could not create trace file {}", path.display()))?;
+
+        Ok(Self {
+            path,
+            started: Instant::now(),
+            writer: BufWriter::new(file),
+            records: 0,
+            flushed: None,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    pub fn record(&mut self, ctl_event: &Ctl1Event) -> eyre::Result<()> {
+        let record = TraceRecord::new(ctl_event, self.started);
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        self.records += 1;
+
+        let now = Instant::now();
+        let flushed = *self.flushed.get_or_insert(now);
+        if now.duration_since(flushed) >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes if records were buffered `FLUSH_INTERVAL` ago or more, for
+    /// when no new event comes to do it.
+    pub fn flush_due(&mut self, now: Instant) -> eyre::Result<()> {
+        match self.flushed {
+            Some(flushed) if now.duration_since(flushed) >= FLUSH_INTERVAL => self.flush(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes out what is still buffered.
+    pub fn finish(mut self) -> eyre::Result<()> {
+        self.flush()
+    }
+
+    fn flush(&mut self) -> eyre::Result<()> {
+        self.writer.flush()?;
+        self.flushed = None;
+        Ok(())
+    }
+}
+
+
+/// Reads a whole trace file, blank lines are skipped.
+pub fn load_trace<P>(path: P) -> eyre::Result<Vec<TraceRecord>>
+    where P: AsRef<Path> {
+    let path = path.as_ref();
+    let file = File::open(path)
+        .wrap_err_with(|| format!("could not open trace file {}", path.display()))?;
+
+    let mut records = Vec::with_capacity(1024);
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .wrap_err_with(|| format!("invalid trace record at {}:{}", path.display(), i + 1))?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+
+/// `ctl1-<unix time>.jsonl` in the working directory.
+pub fn default_trace_path() -> PathBuf {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default();
+    PathBuf::from(format!("ctl1-{}.jsonl", now))
+}
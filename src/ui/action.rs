@@ -5,58 +5,217 @@ use std::slice::Iter;
 
 use crate::ui::key::UiKey;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum UiAction {
     Quit,
+    CycleView,
+    DeadzoneUp,
+    DeadzoneDown,
+    ToggleTimeline,
+    ToggleRecording,
+    ReplayPause,
+    ReplayStep,
+    ReplayFaster,
+    ReplaySlower,
+    FocusPrevious,
+    FocusNext,
+    ToggleZoom,
+    ToggleHelp,
+    ToggleDiagnostics,
+    ToggleRumble,
+    RumblePrevious,
+    RumbleNext,
+    RumbleLess,
+    RumbleMore,
+    RumbleFire,
+    RumbleStop,
+    ScrollUp,
+    ScrollDown,
+    ScrollEnd,
 }
 
 impl UiAction {
     pub fn iterator() -> Iter<'static, Self> {
-        static ACTIONS: [UiAction; 1] = [UiAction::Quit];
+        static ACTIONS: [UiAction; 25] = [
+            UiAction::Quit,
+            UiAction::CycleView,
+            UiAction::DeadzoneUp,
+            UiAction::DeadzoneDown,
+            UiAction::ToggleTimeline,
+            UiAction::ToggleRecording,
+            UiAction::ReplayPause,
+            UiAction::ReplayStep,
+            UiAction::ReplayFaster,
+            UiAction::ReplaySlower,
+            UiAction::FocusPrevious,
+            UiAction::FocusNext,
+            UiAction::ToggleZoom,
+            UiAction::ToggleHelp,
+            UiAction::ToggleDiagnostics,
+            UiAction::ToggleRumble,
+            UiAction::RumblePrevious,
+            UiAction::RumbleNext,
+            UiAction::RumbleLess,
+            UiAction::RumbleMore,
+            UiAction::RumbleFire,
+            UiAction::RumbleStop,
+            UiAction::ScrollUp,
+            UiAction::ScrollDown,
+            UiAction::ScrollEnd,
+        ];
         ACTIONS.iter()
     }
 
+    /// Default key bindings.
     pub fn keys(&self) -> &[UiKey] {
         match self {
             UiAction::Quit => &[UiKey::Ctrl('c'), UiKey::Char('q')],
+            UiAction::CycleView => &[UiKey::Char('v')],
+            UiAction::DeadzoneUp => &[UiKey::Char(']')],
+            UiAction::DeadzoneDown => &[UiKey::Char('[')],
+            UiAction::ToggleTimeline => &[UiKey::Char('t')],
+            UiAction::ToggleRecording => &[UiKey::Char('r')],
+            UiAction::ReplayPause => &[UiKey::Char(' ')],
+            UiAction::ReplayStep => &[UiKey::Char('n')],
+            UiAction::ReplayFaster => &[UiKey::Char('+'), UiKey::Char('=')],
+            UiAction::ReplaySlower => &[UiKey::Char('-')],
+            UiAction::FocusPrevious => &[UiKey::Left, UiKey::BackTab],
+            UiAction::FocusNext => &[UiKey::Right, UiKey::Tab],
+            UiAction::ToggleZoom => &[UiKey::Char('z'), UiKey::Enter],
+            UiAction::ToggleHelp => &[UiKey::Char('?')],
+            UiAction::ToggleDiagnostics => &[UiKey::Char('d')],
+            UiAction::ToggleRumble => &[UiKey::Char('u')],
+            UiAction::RumblePrevious => &[UiKey::Up],
+            UiAction::RumbleNext => &[UiKey::Down],
+            UiAction::RumbleLess => &[UiKey::Char(',')],
+            UiAction::RumbleMore => &[UiKey::Char('.')],
+            UiAction::RumbleFire => &[UiKey::Char('f')],
+            UiAction::RumbleStop => &[UiKey::Char('x')],
+            UiAction::ScrollUp => &[UiKey::PageUp],
+            UiAction::ScrollDown => &[UiKey::PageDown],
+            UiAction::ScrollEnd => &[UiKey::End],
         }
     }
 }
 
+impl UiAction {
+    /// Name of the action in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UiAction::Quit => "quit",
+            UiAction::CycleView => "cycle_view",
+            UiAction::DeadzoneUp => "deadzone_up",
+            UiAction::DeadzoneDown => "deadzone_down",
+            UiAction::ToggleTimeline => "toggle_timeline",
+            UiAction::ToggleRecording => "toggle_recording",
+            UiAction::ReplayPause => "replay_pause",
+            UiAction::ReplayStep => "replay_step",
+            UiAction::ReplayFaster => "replay_faster",
+            UiAction::ReplaySlower => "replay_slower",
+            UiAction::FocusPrevious => "focus_previous",
+            UiAction::FocusNext => "focus_next",
+            UiAction::ToggleZoom => "toggle_zoom",
+            UiAction::ToggleHelp => "toggle_help",
+            UiAction::ToggleDiagnostics => "toggle_diagnostics",
+            UiAction::ToggleRumble => "toggle_rumble",
+            UiAction::RumblePrevious => "rumble_previous",
+            UiAction::RumbleNext => "rumble_next",
+            UiAction::RumbleLess => "rumble_less",
+            UiAction::RumbleMore => "rumble_more",
+            UiAction::RumbleFire => "rumble_fire",
+            UiAction::RumbleStop => "rumble_stop",
+            UiAction::ScrollUp => "scroll_up",
+            UiAction::ScrollDown => "scroll_down",
+            UiAction::ScrollEnd => "scroll_end",
+        }
+    }
+
+    /// One line for the help overlay.
+    pub fn description(&self) -> &'static str {
+        match self {
+            UiAction::Quit => "Quit",
+            UiAction::CycleView => "Cycle the view of the focused panel",
+            UiAction::DeadzoneUp => "Widen the stick deadzone",
+            UiAction::DeadzoneDown => "Narrow the stick deadzone",
+            UiAction::ToggleTimeline => "Show or hide the event timelines",
+            UiAction::ToggleRecording => "Start or stop recording a trace",
+            UiAction::ReplayPause => "Pause or resume the replay",
+            UiAction::ReplayStep => "Replay the next event, paused",
+            UiAction::ReplayFaster => "Replay faster",
+            UiAction::ReplaySlower => "Replay slower",
+            UiAction::FocusPrevious => "Focus the previous controller",
+            UiAction::FocusNext => "Focus the next controller",
+            UiAction::ToggleZoom => "Zoom the focused controller",
+            UiAction::ToggleHelp => "Show or hide this help",
+            UiAction::ToggleDiagnostics => "Show or hide input device diagnostics",
+            UiAction::ToggleRumble => "Show or hide the rumble test panel",
+            UiAction::RumblePrevious => "Select the previous rumble setting",
+            UiAction::RumbleNext => "Select the next rumble setting",
+            UiAction::RumbleLess => "Lower the selected rumble setting",
+            UiAction::RumbleMore => "Raise the selected rumble setting",
+            UiAction::RumbleFire => "Rumble the focused controller",
+            UiAction::RumbleStop => "Stop rumbling the focused controller",
+            UiAction::ScrollUp => "Scroll the timelines back",
+            UiAction::ScrollDown => "Scroll the timelines forward",
+            UiAction::ScrollEnd => "Follow the latest events",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        UiAction::iterator()
+            .find(|action| action.name() == name)
+            .cloned()
+    }
+}
+
 impl Display for UiAction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let str = match self {
             UiAction::Quit => "Action[Quit]",
+            UiAction::CycleView => "Action[CycleView]",
+            UiAction::DeadzoneUp => "Action[DeadzoneUp]",
+            UiAction::DeadzoneDown => "Action[DeadzoneDown]",
+            UiAction::ToggleTimeline => "Action[ToggleTimeline]",
+            UiAction::ToggleRecording => "Action[ToggleRecording]",
+            UiAction::ReplayPause => "Action[ReplayPause]",
+            UiAction::ReplayStep => "Action[ReplayStep]",
+            UiAction::ReplayFaster => "Action[ReplayFaster]",
+            UiAction::ReplaySlower => "Action[ReplaySlower]",
+            UiAction::FocusPrevious => "Action[FocusPrevious]",
+            UiAction::FocusNext => "Action[FocusNext]",
+            UiAction::ToggleZoom => "Action[ToggleZoom]",
+            UiAction::ToggleHelp => "Action[ToggleHelp]",
+            UiAction::ToggleDiagnostics => "Action[ToggleDiagnostics]",
+            UiAction::ToggleRumble => "Action[ToggleRumble]",
+            UiAction::RumblePrevious => "Action[RumblePrevious]",
+            UiAction::RumbleNext => "Action[RumbleNext]",
+            UiAction::RumbleLess => "Action[RumbleLess]",
+            UiAction::RumbleMore => "Action[RumbleMore]",
+            UiAction::RumbleFire => "Action[RumbleFire]",
+            UiAction::RumbleStop => "Action[RumbleStop]",
+            UiAction::ScrollUp => "Action[ScrollUp]",
+            UiAction::ScrollDown => "Action[ScrollDown]",
+            UiAction::ScrollEnd => "Action[ScrollEnd]",
         };
         write!(f, "{}", str)
     }
 }
 
-#[derive(Default, Debug, Clone)]
-pub struct UiActions(Vec<UiAction>);
+/// Active key bindings, at most one action per key.
+#[derive(Debug, Clone)]
+pub struct UiActions(Vec<(UiAction, Vec<UiKey>)>);
 
 impl UiActions {
-    pub fn find(&self, key: UiKey) -> Option<&UiAction> {
-        UiAction::iterator()
-            .filter(|action| self.0.contains(action))
-            .find(|action| action.keys().contains(&key))
-    }
-
-    pub fn actions(&self) -> &[UiAction] {
-        self.0.as_slice()
-    }
-}
-
-impl From<Vec<UiAction>> for UiActions {
-    fn from(actions: Vec<UiAction>) -> Self {
+    /// Fails if a key is bound to more than one action.
+    pub fn new(bindings: Vec<(UiAction, Vec<UiKey>)>) -> eyre::Result<Self> {
         let mut map: HashMap<UiKey, Vec<UiAction>> = HashMap::new();
-        for action in actions.iter() {
-            for key in action.keys().iter() {
+        for (action, keys) in bindings.iter() {
+            for key in keys.iter() {
                 map.entry(*key).or_default().push(*action);
             }
         }
 
-        let errors = map
+        let mut errors = map
             .iter()
             .filter(|(_, actions)| actions.len() > 1)
             .map(|(key, actions)| {
@@ -68,11 +227,49 @@ impl From<Vec<UiAction>> for UiActions {
                 format!("Conflict key {} with actions {}", key, actions)
             })
             .collect::<Vec<_>>();
+        errors.sort();
 
         if !errors.is_empty() {
-            panic!("{}", errors.join("; "));
+            eyre::bail!("{}", errors.join("; "));
         }
 
-        Self(actions)
+        Ok(Self(bindings))
+    }
+
+    /// The default bindings, except for the actions in `overrides`.
+    pub fn with_overrides(overrides: &HashMap<UiAction, Vec<UiKey>>) -> eyre::Result<Self> {
+        let bindings = UiAction::iterator()
+            .map(|action| match overrides.get(action) {
+                Some(keys) => (*action, keys.clone()),
+                None => (*action, action.keys().to_vec()),
+            })
+            .collect();
+
+        Self::new(bindings)
+    }
+
+    pub fn find(&self, key: UiKey) -> Option<&UiAction> {
+        self.0
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(action, _)| action)
+    }
+
+    pub fn keys_of(&self, action: UiAction) -> &[UiKey] {
+        self.0
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn bindings(&self) -> &[(UiAction, Vec<UiKey>)] {
+        self.0.as_slice()
+    }
+}
+
+impl Default for UiActions {
+    fn default() -> Self {
+        Self::with_overrides(&HashMap::new()).expect("default key bindings conflict")
     }
 }
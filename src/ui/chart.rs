@@ -0,0 +1,101 @@
+use std::time::Instant;
+
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Style};
+use tui::symbols::Marker;
+use tui::text::Span;
+use tui::widgets::{Axis, Block, Borders, BorderType, Chart, Dataset, GraphType, Paragraph};
+
+use crate::app::{AxisSamples, AXIS_WINDOW};
+
+pub fn draw_charts<B>(rect: &mut Frame<B>,
+                      area: Rect,
+                      title: String,
+                      axes: Option<&Vec<AxisSamples>>,
+                      now: Instant)
+    where B: Backend {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Plain)
+        .title(title);
+    let inner = block.inner(area);
+    rect.render_widget(block, area);
+
+    let axes = match axes {
+        Some(axes) if !axes.is_empty() => axes,
+        _ => {
+            let empty = Paragraph::new("No analog input yet")
+                .style(Style::default().fg(Color::DarkGray));
+            rect.render_widget(empty, inner);
+            return;
+        }
+    };
+
+    let constraints = vec![Constraint::Ratio(1, axes.len() as u32); axes.len()];
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(&constraints[..])
+        .split(inner);
+
+    for (axis, chunk) in axes.iter().zip(chunks) {
+        draw_chart(rect, chunk, axis, now);
+    }
+}
+
+fn draw_chart<B>(rect: &mut Frame<B>,
+                 area: Rect,
+                 axis: &AxisSamples,
+                 now: Instant)
+    where B: Backend {
+    let window = AXIS_WINDOW.as_secs_f64();
+    let points = axis.window(now);
+    let (min, max, mean) = axis.stats(now).unwrap_or((0.0, 0.0, 0.0));
+    let current = points.last().map(|(_, value)| *value).unwrap_or(0.0);
+
+    // Pressure axes never go below zero, don't waste half the chart on them.
+    let y_bounds = if min >= 0.0 { [0.0, 1.0] } else { [-1.0, 1.0] };
+
+    let min_line = [(-window, min), (0.0, min)];
+    let max_line = [(-window, max), (0.0, max)];
+    let mean_line = [(-window, mean), (0.0, mean)];
+
+    let datasets = vec![
+        Dataset::default()
+            .marker(Marker::Dot)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::DarkGray))
+            .data(&min_line),
+        Dataset::default()
+            .marker(Marker::Dot)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::DarkGray))
+            .data(&max_line),
+        Dataset::default()
+            .marker(Marker::Dot)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Yellow))
+            .data(&mean_line),
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::LightCyan))
+            .data(&points),
+    ];
+
+    let title = Span::styled(
+        format!(
+            "{} {:.2} [min {:.2} max {:.2} mean {:.2}]",
+            axis.name, current, min, max, mean,
+        ),
+        Style::default().fg(Color::LightCyan),
+    );
+
+    let chart = Chart::new(datasets)
+        .block(Block::default().title(title))
+        .x_axis(Axis::default().bounds([-window, 0.0]))
+        .y_axis(Axis::default().bounds(y_bounds));
+
+    rect.render_widget(chart, area);
+}
@@ -0,0 +1,40 @@
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans, Text};
+use tui::widgets::{Block, Borders, BorderType, Paragraph};
+
+const DIAGNOSTICS_WIDTH: u16 = 110;
+
+/// Centered over `size`, shrunk to fit small terminals.
+pub fn diagnostics_area(size: Rect, lines: &[String]) -> Rect {
+    let width = DIAGNOSTICS_WIDTH.min(size.width);
+    let height = (lines.len() as u16 + 2).min(size.height);
+
+    Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+pub fn draw_diagnostics(lines: &[String]) -> Paragraph<'_> {
+    let text = lines
+        .iter()
+        .map(|line| {
+            let style = match line.starts_with("- ") || line.starts_with("  ") {
+                true => Style::default().fg(Color::Yellow),
+                false => Style::default(),
+            };
+            Spans::from(Span::styled(line.as_str(), style))
+        })
+        .collect::<Vec<_>>();
+
+    Paragraph::new(Text::from(text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title("Diagnostics (Esc to close)"),
+        )
+}
@@ -1,373 +1,671 @@
 use std::cmp::Ordering;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use stick::AxisInfo;
 use tui::backend::Backend;
 use tui::Frame;
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Style};
-use tui::text::{Span, Text};
-use tui::widgets::{Block, Borders, BorderType, Cell, Paragraph, Row, Table};
+use tui::text::{Span, Spans, Text};
+use tui::widgets::{Block, Borders, BorderType, Cell, Clear, Paragraph, Row, Table, Wrap};
 use tui_logger::{TuiLoggerLevelOutput, TuiLoggerSmartWidget};
 
-use crate::app::{AppState, AppTui};
-use crate::stick::CtlState;
+use crate::app::{AppState, AppTui, CtlHistory, CtlView, Disconnected, UiAreas};
+use crate::stick::{axis_name, CtlInfo, CtlState};
+use crate::stick::pipeline::CtlPipelineStats;
+use crate::ui::action::UiAction;
+use crate::ui::chart::draw_charts;
+use crate::ui::diagnostics::{diagnostics_area, draw_diagnostics};
+use crate::ui::gamepad::draw_layout;
+use crate::ui::help::{draw_help, help_area};
+use crate::ui::raw::draw_raw;
+use crate::ui::rumble::{draw_rumble, rumble_area};
+use crate::ui::sticks::draw_sticks;
+
+/// Below this, only a placeholder is drawn.
+const MIN_WIDTH: u16 = 30;
+const MIN_HEIGHT: u16 = 8;
+/// Below this height the log pane is hidden.
+const LOG_MIN_HEIGHT: u16 = 28;
+/// Below this height the title loses its border.
+const TITLE_MIN_HEIGHT: u16 = 16;
+/// Below this height a panel does not split off its timeline.
+const TIMELINE_MIN_HEIGHT: u16 = 12;
+/// Panels narrower than this are paged instead.
+const PANEL_MIN_WIDTH: u16 = 50;
+/// Narrowest field table that fits two fields to a row.
+const PAIRED_MIN_WIDTH: u16 = 44;
+const RUMBLE_BUTTON: &str = " Rumble ";
 
 pub fn draw<B>(rect: &mut Frame<B>,
-               app_tui: &AppTui,
+               app_tui: &mut AppTui,
                ui_state: &Arc<Mutex<AppState>>)
     where B: Backend {
     let size = rect.size();
-    check_size(&size);
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        rect.render_widget(draw_too_small(&size), size);
+        return;
+    }
 
+    let compact = size.height < TITLE_MIN_HEIGHT;
+    let mut constraints = vec![
+        Constraint::Length(if compact { 1 } else { 3 }),
+        Constraint::Min(5),
+    ];
+    if size.height >= LOG_MIN_HEIGHT {
+        constraints.push(Constraint::Length(10));
+    }
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(10), Constraint::Length(10)].as_ref())
+        .constraints(constraints)
         .split(size);
 
-    let title = draw_title();
+    let app = ui_state.lock().unwrap();
+    let mut ids = app.ids();
+
+    let focused = app_tui.focused.and_then(|id| ids.iter().position(|i| *i == id));
+    if focused.is_none() {
+        app_tui.focused = None;
+        app_tui.zoomed = false;
+    }
+    if let (true, Some(index)) = (app_tui.zoomed, focused) {
+        ids = vec![ids[index]];
+    }
+
+    // The page follows the focus, so the focused panel is always shown.
+    let per_page = (size.width / PANEL_MIN_WIDTH).max(1) as usize;
+    let pages = ids.len().div_ceil(per_page).max(1);
+    let page = match (app_tui.zoomed, focused) {
+        (false, Some(index)) => index / per_page,
+        _ => 0,
+    };
+    let start = page * per_page;
+    let page_ids = ids[start..ids.len().min(start + per_page)].to_vec();
+
+    let title = draw_title(&app, compact, (page, pages));
     rect.render_widget(title, chunks[0]);
 
-    draw_ctls(ui_state, rect, &chunks[1]);
+    app_tui.areas = UiAreas::default();
+    draw_ctls(app_tui, &app, page_ids, rect, &chunks[1]);
 
-    if let Some(logger_widget) = draw_log(app_tui) {
-        rect.render_widget(logger_widget, chunks[2]);
+    if let (Some(logger_widget), Some(area)) = (draw_log(app_tui), chunks.get(2)) {
+        rect.render_widget(logger_widget, *area);
+        app_tui.areas.log = Some(*area);
+    }
+
+    if app_tui.rumble_panel {
+        let target = match app_tui.focused.or_else(|| ids.first().cloned()) {
+            Some(id) if can_rumble(&app, id) => format!("#{}", id),
+            Some(id) => format!("#{} (unsupported)", id),
+            None => "(no controller)".to_string(),
+        };
+        let area = rumble_area(chunks[1]);
+        rect.render_widget(Clear, area);
+        rect.render_widget(draw_rumble(&app_tui.rumble, target), area);
+    }
+
+    if app_tui.help {
+        let area = help_area(size, app_tui.actions());
+        rect.render_widget(Clear, area);
+        rect.render_widget(draw_help(app_tui.actions()), area);
+    }
+
+    if let Some(lines) = &app_tui.diagnostics {
+        let area = diagnostics_area(size, lines);
+        rect.render_widget(Clear, area);
+        rect.render_widget(draw_diagnostics(lines), area);
     }
 }
 
-fn draw_ctls<B>(app: &Arc<Mutex<AppState>>,
+fn draw_ctls<B>(app_tui: &mut AppTui,
+                app: &AppState,
+                ids: Vec<u64>,
                 rect: &mut Frame<B>,
                 chunk: &Rect)
     where B: Backend {
-    let app = {
-        app.lock().unwrap().clone()
-    };
-
-    let mut tables = vec![];
-    for (id, current) in app.current.iter() {
-        let previous = &app.previous[id];
-        let name = &app.name_mapping[id];
-        let by_ctl = draw_ctl(name, current, previous);
-        tables.push(by_ctl);
+    if ids.is_empty() {
+        let keys = app_tui
+            .actions()
+            .keys_of(UiAction::ToggleDiagnostics)
+            .iter()
+            .map(|key| key.to_string())
+            .collect::<Vec<_>>();
+        let hint = match (app.source_failure.get(), keys.first()) {
+            (Some(reason), Some(key)) => Some((format!("{}, press {} for diagnostics", reason, key), Color::LightRed)),
+            (Some(reason), None) => Some((reason, Color::LightRed)),
+            (None, Some(key)) => Some((format!("No controllers, press {} for diagnostics", key), Color::DarkGray)),
+            (None, None) => None,
+        };
+        if let Some((hint, color)) = hint {
+            let hint = Paragraph::new(hint)
+                .style(Style::default().fg(color))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            rect.render_widget(hint, *chunk);
+        }
+        return;
     }
 
-    let constraints = vec![Constraint::Min(50); tables.len()];
+    let constraints = vec![Constraint::Ratio(1, ids.len() as u32); ids.len()];
     let body_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(&constraints[..])
         .split(*chunk);
 
-    for (i, p) in tables.into_iter().enumerate() {
-        rect.render_widget(p, body_chunks[i]);
+    for (i, id) in ids.into_iter().enumerate() {
+        let current = &app.current[&id];
+        let previous = &app.previous[&id];
+        let info = &app.info[&id];
+        let stats = app.pipeline.get(&id).cloned().unwrap_or_default();
+        let disconnected = app.disconnected.get(&id);
+        let title = ctl_title(info, app_tui.focused == Some(id), app_tui.zoomed, disconnected);
+
+        let panel = if app_tui.timeline && chunk.height >= TIMELINE_MIN_HEIGHT {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+                .split(body_chunks[i])
+        } else {
+            vec![body_chunks[i]]
+        };
+
+        match app_tui.view(id) {
+            CtlView::Table => {
+                rect.render_widget(draw_ctl(title, &stats, &info.axes, current, previous, &panel[0]), panel[0]);
+            }
+            CtlView::Charts => {
+                draw_charts(rect, panel[0], title, app.axes.get(&id), Instant::now());
+            }
+            CtlView::Sticks => {
+                draw_sticks(rect,
+                            panel[0],
+                            title,
+                            app.axes.get(&id),
+                            app_tui.deadzone,
+                            Instant::now());
+            }
+            CtlView::Layout => {
+                rect.render_widget(draw_layout(title, info, current), panel[0]);
+            }
+            CtlView::Raw => {
+                rect.render_widget(draw_raw(title, app.history.get(&id), panel[0].height), panel[0]);
+            }
+        }
+
+        app_tui.areas.panels.push((id, body_chunks[i]));
+        if can_rumble(app, id) {
+            if let Some(area) = rumble_button_area(&panel[0]) {
+                rect.render_widget(draw_rumble_button(), area);
+                app_tui.areas.rumble_buttons.push((id, area));
+            }
+        }
+
+        if let (Some(history), Some(area)) = (app.history.get(&id), panel.get(1)) {
+            let timeline = draw_timeline(history, app_tui.timeline_scroll, area.height);
+            rect.render_widget(timeline, *area);
+            app_tui.areas.timelines.push(*area);
+        }
+
+        if disconnected.is_some() {
+            // Greys out whatever the view drew.
+            let grey = Block::default().style(Style::default().fg(Color::DarkGray));
+            rect.render_widget(grey, body_chunks[i]);
+        }
+    }
+}
+
+/// On the top border, right aligned.
+fn rumble_button_area(panel: &Rect) -> Option<Rect> {
+    let width = RUMBLE_BUTTON.chars().count() as u16;
+    if panel.width < width + 24 {
+        return None;
     }
+
+    Some(Rect::new(panel.x + panel.width - width - 2, panel.y, width, 1))
+}
+
+/// Whether controller `id` is connected and can rumble.
+fn can_rumble(app: &AppState, id: u64) -> bool {
+    app.commands.accepts(id) && app.info.get(&id).is_some_and(|info| info.rumble)
+}
+
+fn draw_rumble_button<'a>() -> Paragraph<'a> {
+    Paragraph::new(RUMBLE_BUTTON)
+        .style(Style::default().fg(Color::Black).bg(Color::Gray))
+}
+
+fn draw_timeline<'a>(history: &CtlHistory, scroll: usize, height: u16) -> Paragraph<'a> {
+    let style0 = Style::default().fg(Color::LightCyan);
+    let style1 = Style::default().fg(Color::Gray);
+
+    let visible = height.saturating_sub(2) as usize;
+    let total = history.events.len();
+    let scroll = scroll.min(total.saturating_sub(visible));
+    let end = total - scroll;
+    let start = end.saturating_sub(visible);
+
+    let mut lines = Vec::with_capacity(visible);
+    for i in start..end {
+        let ctl_event = &history.events[i];
+        let since_start = ctl_event.at.duration_since(history.started);
+        let since_previous = match i {
+            0 => Duration::ZERO,
+            _ => ctl_event.at.duration_since(history.events[i - 1].at),
+        };
+
+        lines.push(Spans::from(vec![
+            Span::styled(format!("{:>9.3}s ", since_start.as_secs_f64()), style0),
+            Span::styled(format!("+{:>8.1}ms ", since_previous.as_secs_f64() * 1000.0), style1),
+            Span::styled(ctl_event.triggering_event.to_string(), style0),
+        ]));
+    }
+
+    let title = if scroll > 0 {
+        format!("Timeline [{}-{}/{}]", start + 1, end, total)
+    } else {
+        "Timeline".to_string()
+    };
+
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(title),
+        )
 }
 
-fn draw_ctl<'a>(name: &str, current: &CtlState, previous: &CtlState) -> Table<'a> {
+fn draw_ctl<'a>(title: String,
+                stats: &CtlPipelineStats,
+                axes: &[AxisInfo],
+                current: &CtlState,
+                previous: &CtlState,
+                area: &Rect) -> Table<'a> {
+    // Cells of each field, laid out once they are all known.
     let mut rows = Vec::with_capacity(128);
 
     if current.exit.is_some() {
-        rows.push(bool_row("Exit", previous.exit, current.exit));
+        rows.push(bool_cells("Exit", previous.exit, current.exit));
     }
     if current.action_a.is_some() {
-        rows.push(bool_row("Action A", previous.action_a, current.action_a));
+        rows.push(bool_cells("Action A", previous.action_a, current.action_a));
     }
     if current.action_b.is_some() {
-        rows.push(bool_row("Action B", previous.action_b, current.action_b));
+        rows.push(bool_cells("Action B", previous.action_b, current.action_b));
     }
     if current.action_c.is_some() {
-        rows.push(bool_row("Action C", previous.action_c, current.action_c));
+        rows.push(bool_cells("Action C", previous.action_c, current.action_c));
     }
     if current.action_h.is_some() {
-        rows.push(bool_row("Action H", previous.action_h, current.action_h));
+        rows.push(bool_cells("Action H", previous.action_h, current.action_h));
     }
     if current.action_v.is_some() {
-        rows.push(bool_row("Action V", previous.action_v, current.action_v));
+        rows.push(bool_cells("Action V", previous.action_v, current.action_v));
     }
     if current.action_d.is_some() {
-        rows.push(bool_row("Action D", previous.action_d, current.action_d));
+        rows.push(bool_cells("Action D", previous.action_d, current.action_d));
     }
     if current.menu_l.is_some() {
-        rows.push(bool_row("Menu L", previous.menu_l, current.menu_l));
+        rows.push(bool_cells("Menu L", previous.menu_l, current.menu_l));
     }
     if current.menu_r.is_some() {
-        rows.push(bool_row("Menu R", previous.menu_r, current.menu_r));
+        rows.push(bool_cells("Menu R", previous.menu_r, current.menu_r));
     }
     if current.joy.is_some() {
-        rows.push(bool_row("Joy", previous.joy, current.joy));
+        rows.push(bool_cells("Joy", previous.joy, current.joy));
     }
     if current.cam.is_some() {
-        rows.push(bool_row("Cam", previous.cam, current.cam));
+        rows.push(bool_cells("Cam", previous.cam, current.cam));
     }
     if current.bumper_l.is_some() {
-        rows.push(bool_row("Bumper L", previous.bumper_l, current.bumper_l));
+        rows.push(bool_cells("Bumper L", previous.bumper_l, current.bumper_l));
     }
     if current.bumper_r.is_some() {
-        rows.push(bool_row("Bumper R", previous.bumper_r, current.bumper_r));
+        rows.push(bool_cells("Bumper R", previous.bumper_r, current.bumper_r));
     }
     if current.trigger_l.is_some() {
-        rows.push(f64_row("Trigger L", previous.trigger_l, current.trigger_l));
+        rows.push(f64_cells("Trigger L", previous.trigger_l, current.trigger_l));
     }
     if current.trigger_r.is_some() {
-        rows.push(f64_row("Trigger R", previous.trigger_r, current.trigger_r));
+        rows.push(f64_cells("Trigger R", previous.trigger_r, current.trigger_r));
     }
     if current.up.is_some() {
-        rows.push(bool_row("Up", previous.up, current.up));
+        rows.push(bool_cells("Up", previous.up, current.up));
     }
     if current.down.is_some() {
-        rows.push(bool_row("Down", previous.down, current.down));
+        rows.push(bool_cells("Down", previous.down, current.down));
     }
     if current.left.is_some() {
-        rows.push(bool_row("Left", previous.left, current.left));
+        rows.push(bool_cells("Left", previous.left, current.left));
     }
     if current.right.is_some() {
-        rows.push(bool_row("Right", previous.right, current.right));
+        rows.push(bool_cells("Right", previous.right, current.right));
     }
     if current.pov_up.is_some() {
-        rows.push(bool_row("Pov Up", previous.pov_up, current.pov_up));
+        rows.push(bool_cells("Pov Up", previous.pov_up, current.pov_up));
     }
     if current.pov_down.is_some() {
-        rows.push(bool_row("Pov Down", previous.pov_down, current.pov_down));
+        rows.push(bool_cells("Pov Down", previous.pov_down, current.pov_down));
     }
     if current.pov_left.is_some() {
-        rows.push(bool_row("Pov Left", previous.pov_left, current.pov_left));
+        rows.push(bool_cells("Pov Left", previous.pov_left, current.pov_left));
     }
     if current.pov_right.is_some() {
-        rows.push(bool_row("Pov Right", previous.pov_right, current.pov_right));
+        rows.push(bool_cells("Pov Right", previous.pov_right, current.pov_right));
     }
     if current.hat_up.is_some() {
-        rows.push(bool_row("Hat Up", previous.hat_up, current.hat_up));
+        rows.push(bool_cells("Hat Up", previous.hat_up, current.hat_up));
     }
     if current.hat_down.is_some() {
-        rows.push(bool_row("Hat Down", previous.hat_down, current.hat_down));
+        rows.push(bool_cells("Hat Down", previous.hat_down, current.hat_down));
     }
     if current.hat_left.is_some() {
-        rows.push(bool_row("Hat Left", previous.hat_left, current.hat_left));
+        rows.push(bool_cells("Hat Left", previous.hat_left, current.hat_left));
     }
     if current.hat_right.is_some() {
-        rows.push(bool_row("Hat Right", previous.hat_right, current.hat_right));
+        rows.push(bool_cells("Hat Right", previous.hat_right, current.hat_right));
     }
     if current.trim_up.is_some() {
-        rows.push(bool_row("Trim Up", previous.trim_up, current.trim_up));
+        rows.push(bool_cells("Trim Up", previous.trim_up, current.trim_up));
     }
     if current.trim_down.is_some() {
-        rows.push(bool_row("Trim Down", previous.trim_down, current.trim_down));
+        rows.push(bool_cells("Trim Down", previous.trim_down, current.trim_down));
     }
     if current.trim_left.is_some() {
-        rows.push(bool_row("Trim Left", previous.trim_left, current.trim_left));
+        rows.push(bool_cells("Trim Left", previous.trim_left, current.trim_left));
     }
     if current.trim_right.is_some() {
-        rows.push(bool_row("Trim Right", previous.trim_right, current.trim_right));
+        rows.push(bool_cells("Trim Right", previous.trim_right, current.trim_right));
     }
     if current.mic_up.is_some() {
-        rows.push(bool_row("Mic Up", previous.mic_up, current.mic_up));
+        rows.push(bool_cells("Mic Up", previous.mic_up, current.mic_up));
     }
     if current.mic_down.is_some() {
-        rows.push(bool_row("Mic Down", previous.mic_down, current.mic_down));
+        rows.push(bool_cells("Mic Down", previous.mic_down, current.mic_down));
     }
     if current.mic_left.is_some() {
-        rows.push(bool_row("Mic Left", previous.mic_left, current.mic_left));
+        rows.push(bool_cells("Mic Left", previous.mic_left, current.mic_left));
     }
     if current.mic_right.is_some() {
-        rows.push(bool_row("Mic Right", previous.mic_right, current.mic_right));
+        rows.push(bool_cells("Mic Right", previous.mic_right, current.mic_right));
     }
     if current.joy_x.is_some() {
-        rows.push(f64_row("Joy X", previous.joy_x, current.joy_x));
+        rows.push(f64_cells("Joy X", previous.joy_x, current.joy_x));
     }
     if current.joy_y.is_some() {
-        rows.push(f64_row("Joy Y", previous.joy_y, current.joy_y));
+        rows.push(f64_cells("Joy Y", previous.joy_y, current.joy_y));
     }
     if current.joy_z.is_some() {
-        rows.push(f64_row("Joy Z", previous.joy_z, current.joy_z));
+        rows.push(f64_cells("Joy Z", previous.joy_z, current.joy_z));
     }
     if current.cam_x.is_some() {
-        rows.push(f64_row("Cam X", previous.cam_x, current.cam_x));
+        rows.push(f64_cells("Cam X", previous.cam_x, current.cam_x));
     }
     if current.cam_y.is_some() {
-        rows.push(f64_row("Cam Y", previous.cam_y, current.cam_y));
+        rows.push(f64_cells("Cam Y", previous.cam_y, current.cam_y));
     }
     if current.cam_z.is_some() {
-        rows.push(f64_row("Cam Z", previous.cam_z, current.cam_z));
+        rows.push(f64_cells("Cam Z", previous.cam_z, current.cam_z));
     }
     if current.slew.is_some() {
-        rows.push(f64_row("Slew", previous.slew, current.slew));
+        rows.push(f64_cells("Slew", previous.slew, current.slew));
     }
     if current.throttle.is_some() {
-        rows.push(f64_row("Throttle", previous.throttle, current.throttle));
+        rows.push(f64_cells("Throttle", previous.throttle, current.throttle));
     }
     if current.throttle_l.is_some() {
-        rows.push(f64_row("Throttle L", previous.throttle_l, current.throttle_l));
+        rows.push(f64_cells("Throttle L", previous.throttle_l, current.throttle_l));
     }
     if current.throttle_r.is_some() {
-        rows.push(f64_row("Throttle R", previous.throttle_r, current.throttle_r));
+        rows.push(f64_cells("Throttle R", previous.throttle_r, current.throttle_r));
     }
     if current.volume.is_some() {
-        rows.push(f64_row("Volume", previous.volume, current.volume));
+        rows.push(f64_cells("Volume", previous.volume, current.volume));
     }
     if current.wheel.is_some() {
-        rows.push(f64_row("Wheel", previous.wheel, current.wheel));
+        rows.push(f64_cells("Wheel", previous.wheel, current.wheel));
     }
     if current.rudder.is_some() {
-        rows.push(f64_row("Rudder", previous.rudder, current.rudder));
+        rows.push(f64_cells("Rudder", previous.rudder, current.rudder));
     }
     if current.gas.is_some() {
-        rows.push(f64_row("Gas", previous.gas, current.gas));
+        rows.push(f64_cells("Gas", previous.gas, current.gas));
     }
     if current.brake.is_some() {
-        rows.push(f64_row("Brake", previous.brake, current.brake));
+        rows.push(f64_cells("Brake", previous.brake, current.brake));
     }
     if current.mic_push.is_some() {
-        rows.push(bool_row("Mic Push", previous.mic_push, current.mic_push));
+        rows.push(bool_cells("Mic Push", previous.mic_push, current.mic_push));
     }
     if current.trigger.is_some() {
-        rows.push(bool_row("Trigger", previous.trigger, current.trigger));
+        rows.push(bool_cells("Trigger", previous.trigger, current.trigger));
     }
     if current.bumper.is_some() {
-        rows.push(f64_row("Bumper", previous.bumper, current.bumper));
+        rows.push(f64_cells("Bumper", previous.bumper, current.bumper));
     }
     if current.action_m.is_some() {
-        rows.push(bool_row("Action M", previous.action_m, current.action_m));
+        rows.push(bool_cells("Action M", previous.action_m, current.action_m));
     }
     if current.action_l.is_some() {
-        rows.push(bool_row("Action L", previous.action_l, current.action_l));
+        rows.push(bool_cells("Action L", previous.action_l, current.action_l));
     }
     if current.action_r.is_some() {
-        rows.push(bool_row("Action R", previous.action_r, current.action_r));
+        rows.push(bool_cells("Action R", previous.action_r, current.action_r));
     }
     if current.pinky.is_some() {
-        rows.push(bool_row("Pinky", previous.pinky, current.pinky));
+        rows.push(bool_cells("Pinky", previous.pinky, current.pinky));
     }
     if current.pinky_forward.is_some() {
-        rows.push(bool_row("Pinky Forward", previous.pinky_forward, current.pinky_forward));
+        rows.push(bool_cells("Pinky Forward", previous.pinky_forward, current.pinky_forward));
     }
     if current.pinky_backward.is_some() {
-        rows.push(bool_row("Pinky Backward", previous.pinky_backward, current.pinky_backward));
+        rows.push(bool_cells("Pinky Backward", previous.pinky_backward, current.pinky_backward));
     }
     if current.flaps_up.is_some() {
-        rows.push(bool_row("Flaps Up", previous.flaps_up, current.flaps_up));
+        rows.push(bool_cells("Flaps Up", previous.flaps_up, current.flaps_up));
     }
     if current.flaps_down.is_some() {
-        rows.push(bool_row("Flaps Down", previous.flaps_down, current.flaps_down));
+        rows.push(bool_cells("Flaps Down", previous.flaps_down, current.flaps_down));
     }
     if current.boat_forward.is_some() {
-        rows.push(bool_row("Boat Forward", previous.boat_forward, current.boat_forward));
+        rows.push(bool_cells("Boat Forward", previous.boat_forward, current.boat_forward));
     }
     if current.boat_backward.is_some() {
-        rows.push(bool_row("Boat Backward", previous.boat_backward, current.boat_backward));
+        rows.push(bool_cells("Boat Backward", previous.boat_backward, current.boat_backward));
     }
     if current.autopilot_path.is_some() {
-        rows.push(bool_row("Autopilot Path", previous.autopilot_path, current.autopilot_path));
+        rows.push(bool_cells("Autopilot Path", previous.autopilot_path, current.autopilot_path));
     }
     if current.autopilot_alt.is_some() {
-        rows.push(bool_row("Autopilot Alt", previous.autopilot_alt, current.autopilot_alt));
+        rows.push(bool_cells("Autopilot Alt", previous.autopilot_alt, current.autopilot_alt));
     }
     if current.engine_motor_l.is_some() {
-        rows.push(bool_row("Engine Motor L", previous.engine_motor_l, current.engine_motor_l));
+        rows.push(bool_cells("Engine Motor L", previous.engine_motor_l, current.engine_motor_l));
     }
     if current.engine_motor_r.is_some() {
-        rows.push(bool_row("Engine Motor R", previous.engine_motor_r, current.engine_motor_r));
+        rows.push(bool_cells("Engine Motor R", previous.engine_motor_r, current.engine_motor_r));
     }
     if current.engine_fuel_flow_l.is_some() {
-        rows.push(bool_row("Engine Fuel Flow L", previous.engine_fuel_flow_l, current.engine_fuel_flow_l));
+        rows.push(bool_cells("Engine Fuel Flow L", previous.engine_fuel_flow_l, current.engine_fuel_flow_l));
     }
     if current.engine_fuel_flow_r.is_some() {
-        rows.push(bool_row("Engine Fuel Flow R", previous.engine_fuel_flow_r, current.engine_fuel_flow_r));
+        rows.push(bool_cells("Engine Fuel Flow R", previous.engine_fuel_flow_r, current.engine_fuel_flow_r));
     }
     if current.engine_ignite_l.is_some() {
-        rows.push(bool_row("Engine Ignite L", previous.engine_ignite_l, current.engine_ignite_l));
+        rows.push(bool_cells("Engine Ignite L", previous.engine_ignite_l, current.engine_ignite_l));
     }
     if current.engine_ignite_r.is_some() {
-        rows.push(bool_row("Engine Ignite R", previous.engine_ignite_r, current.engine_ignite_r));
+        rows.push(bool_cells("Engine Ignite R", previous.engine_ignite_r, current.engine_ignite_r));
     }
     if current.speedbrake_backward.is_some() {
-        rows.push(bool_row("Speedbrake Backward", previous.speedbrake_backward, current.speedbrake_backward));
+        rows.push(bool_cells("Speedbrake Backward", previous.speedbrake_backward, current.speedbrake_backward));
     }
     if current.speedbrake_forward.is_some() {
-        rows.push(bool_row("Speedbrake Forward", previous.speedbrake_forward, current.speedbrake_forward));
+        rows.push(bool_cells("Speedbrake Forward", previous.speedbrake_forward, current.speedbrake_forward));
     }
     if current.china_backward.is_some() {
-        rows.push(bool_row("China Backward", previous.china_backward, current.china_backward));
+        rows.push(bool_cells("China Backward", previous.china_backward, current.china_backward));
     }
     if current.china_forward.is_some() {
-        rows.push(bool_row("China Forward", previous.china_forward, current.china_forward));
+        rows.push(bool_cells("China Forward", previous.china_forward, current.china_forward));
     }
     if current.apu.is_some() {
-        rows.push(bool_row("APU", previous.apu, current.apu));
+        rows.push(bool_cells("APU", previous.apu, current.apu));
     }
     if current.radar_altimeter.is_some() {
-        rows.push(bool_row("Radar Altimeter", previous.radar_altimeter, current.radar_altimeter));
+        rows.push(bool_cells("Radar Altimeter", previous.radar_altimeter, current.radar_altimeter));
     }
     if current.landing_gear_silence.is_some() {
-        rows.push(bool_row("Landing Gear Silence", previous.landing_gear_silence, current.landing_gear_silence));
+        rows.push(bool_cells("Landing Gear Silence", previous.landing_gear_silence, current.landing_gear_silence));
     }
     if current.eac.is_some() {
-        rows.push(bool_row("EAC", previous.eac, current.eac));
+        rows.push(bool_cells("EAC", previous.eac, current.eac));
     }
     if current.autopilot_toggle.is_some() {
-        rows.push(bool_row("Autopilot Toggle", previous.autopilot_toggle, current.autopilot_toggle));
+        rows.push(bool_cells("Autopilot Toggle", previous.autopilot_toggle, current.autopilot_toggle));
     }
     if current.throttle_button.is_some() {
-        rows.push(bool_row("Throttle Button", previous.throttle_button, current.throttle_button));
+        rows.push(bool_cells("Throttle Button", previous.throttle_button, current.throttle_button));
     }
     if current.mouse_x.is_some() {
-        rows.push(f64_row("Mouse X", previous.mouse_x, current.mouse_x));
+        rows.push(f64_cells("Mouse X", previous.mouse_x, current.mouse_x));
     }
     if current.mouse_y.is_some() {
-        rows.push(f64_row("Mouse Y", previous.mouse_y, current.mouse_y));
+        rows.push(f64_cells("Mouse Y", previous.mouse_y, current.mouse_y));
     }
     if current.mouse.is_some() {
-        rows.push(bool_row("Mouse", previous.mouse, current.mouse));
+        rows.push(bool_cells("Mouse", previous.mouse, current.mouse));
     }
     if current.paddle_left.is_some() {
-        rows.push(bool_row("Paddle L", previous.paddle_left, current.paddle_left));
+        rows.push(bool_cells("Paddle L", previous.paddle_left, current.paddle_left));
     }
     if current.paddle_right.is_some() {
-        rows.push(bool_row("Paddle R", previous.paddle_right, current.paddle_right));
+        rows.push(bool_cells("Paddle R", previous.paddle_right, current.paddle_right));
     }
     if current.pinky_left.is_some() {
-        rows.push(bool_row("Pinky L", previous.pinky_left, current.pinky_left));
+        rows.push(bool_cells("Pinky L", previous.pinky_left, current.pinky_left));
     }
     if current.pinky_right.is_some() {
-        rows.push(bool_row("Pinky R", previous.pinky_right, current.pinky_right));
+        rows.push(bool_cells("Pinky R", previous.pinky_right, current.pinky_right));
     }
     if current.context.is_some() {
-        rows.push(bool_row("Context", previous.context, current.context));
+        rows.push(bool_cells("Context", previous.context, current.context));
     }
     if current.dpi.is_some() {
-        rows.push(bool_row("DPI", previous.dpi, current.dpi));
+        rows.push(bool_cells("DPI", previous.dpi, current.dpi));
     }
     if current.scroll_x.is_some() {
-        rows.push(f64_row("Scroll X", previous.scroll_x, current.scroll_x));
+        rows.push(f64_cells("Scroll X", previous.scroll_x, current.scroll_x));
     }
     if current.scroll_y.is_some() {
-        rows.push(f64_row("Scroll Y", previous.scroll_y, current.scroll_y));
+        rows.push(f64_cells("Scroll Y", previous.scroll_y, current.scroll_y));
     }
     if current.scroll.is_some() {
-        rows.push(bool_row("Scroll", previous.scroll, current.scroll));
+        rows.push(bool_cells("Scroll", previous.scroll, current.scroll));
     }
     if current.action_wheel_x.is_some() {
-        rows.push(f64_row("Action Wheel X", previous.action_wheel_x, current.action_wheel_x));
+        rows.push(f64_cells("Action Wheel X", previous.action_wheel_x, current.action_wheel_x));
     }
     if current.action_wheel_y.is_some() {
-        rows.push(f64_row("Action Wheel Y", previous.action_wheel_y, current.action_wheel_y));
+        rows.push(f64_cells("Action Wheel Y", previous.action_wheel_y, current.action_wheel_y));
     }
 
     for (num, _) in current.number.iter() {
-        rows.push(bool_row(&format!("Num#{}", num),
-                           previous.number.get(num).cloned(),
-                           current.number.get(num).cloned()));
-    }
+        rows.push(bool_cells(&format!("Num#{}", num),
+                             previous.number.get(num).cloned(),
+                             current.number.get(num).cloned()));
+    }
+
+    let visible = area.height.saturating_sub(2) as usize;
+    let fields = rows.len();
+    let (rows, widths, per_row) = if 1 + fields + axes.len() <= visible {
+        let mut table = Vec::with_capacity(1 + fields + axes.len());
+        table.push(stats_row(stats));
+        table.extend(rows.into_iter().map(Row::new));
+        table.extend(axes.iter().map(axis_info_row));
+        (table, &[Constraint::Length(10), Constraint::Min(20), Constraint::Min(10)][..], 1)
+    } else if area.width >= PAIRED_MIN_WIDTH {
+        // Short panel: the fields only, two to a row.
+        let mut cells = rows.into_iter();
+        let mut table = Vec::with_capacity(visible);
+        while let Some(mut row) = cells.next() {
+            row.extend(cells.next().unwrap_or_default());
+            table.push(Row::new(row));
+        }
+        (table, &[
+            Constraint::Length(10), Constraint::Length(1), Constraint::Length(6),
+            Constraint::Length(10), Constraint::Length(1), Constraint::Min(6),
+        ][..], 2)
+    } else {
+        let table = rows.into_iter().map(Row::new).collect();
+        (table, &[Constraint::Length(10), Constraint::Length(1), Constraint::Min(6)][..], 1)
+    };
+
+    let title = match fields.saturating_sub(visible * per_row) {
+        0 => title,
+        hidden => format!("{} [+{} more]", title, hidden),
+    };
 
     Table::new(rows)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Plain)
-                .title(name.to_string()),
+                .title(title),
         )
-        .widths(&[Constraint::Length(10), Constraint::Min(20), Constraint::Min(10)])
+        .widths(widths)
         .column_spacing(1)
 }
 
-fn f64_row(name: &str, prev: Option<f64>, curr: Option<f64>) -> Row {
+fn ctl_title(info: &CtlInfo, focused: bool, zoomed: bool, disconnected: Option<&Disconnected>) -> String {
+    let mut title = format!("{} ({}, model={:016X})", info.name, info, info.model);
+    if let Some(disconnected) = disconnected {
+        title = format!("{} disconnected at {}", title, clock_time(disconnected.wall));
+    }
+    match (focused, zoomed) {
+        (true, true) => format!("● {} [zoom]", title),
+        (true, false) => format!("● {}", title),
+        _ => title,
+    }
+}
+
+/// Time of day of `time`, `HH:MM:SS` in UTC.
+fn clock_time(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default() % 86400;
+    format!("{:02}:{:02}:{:02} UTC", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+fn stats_row<'a>(stats: &CtlPipelineStats) -> Row<'a> {
+    let style = Style::default().fg(Color::DarkGray);
+
+    Row::new(vec![
+        Cell::from(Span::styled("Events", style)),
+        Cell::from(Span::styled(stats.delivered.to_string(), style)),
+        Cell::from(Span::styled(
+            format!("coalesced={} dropped={}", stats.coalesced, stats.dropped),
+            style,
+        )),
+    ])
+}
+
+fn axis_info_row<'a>(axis: &AxisInfo) -> Row<'a> {
+    let style = Style::default().fg(Color::DarkGray);
+
+    Row::new(vec![
+        Cell::from(Span::styled(axis_name(axis.event), style)),
+        Cell::from(Span::styled(format!("{}..{}", axis.minimum, axis.maximum), style)),
+        Cell::from(Span::styled(
+            format!("fuzz={} flat={} res={}", axis.fuzz, axis.flat, axis.resolution),
+            style,
+        )),
+    ])
+}
+
+fn f64_cells<'a>(name: &str, prev: Option<f64>, curr: Option<f64>) -> Vec<Cell<'a>> {
     let style0 = Style::default().fg(Color::LightCyan);
     let style1 = Style::default().fg(Color::Gray);
 
@@ -407,10 +705,10 @@ fn f64_row(name: &str, prev: Option<f64>, curr: Option<f64>) -> Row {
         }
     }
 
-    Row::new(rows)
+    rows
 }
 
-fn bool_row<'a>(name: &str, prev: Option<bool>, curr: Option<bool>) -> Row<'a> {
+fn bool_cells<'a>(name: &str, prev: Option<bool>, curr: Option<bool>) -> Vec<Cell<'a>> {
     let style0 = Style::default().fg(Color::LightCyan);
     let style1 = Style::default().fg(Color::Gray);
 
@@ -457,7 +755,7 @@ fn bool_row<'a>(name: &str, prev: Option<bool>, curr: Option<bool>) -> Row<'a> {
         }
     }
 
-    Row::new(rows)
+    rows
 }
 
 
@@ -477,16 +775,62 @@ fn draw_log<'a>(app_tui: &AppTui) -> Option<TuiLoggerSmartWidget<'a>> {
         .state(&app_tui.tui))
 }
 
-fn draw_title<'a>() -> Paragraph<'a> {
-    Paragraph::new("Driver1 Ctl")
+fn draw_title<'a>(app: &AppState, compact: bool, (page, pages): (usize, usize)) -> Paragraph<'a> {
+    let mut title = vec![Span::raw("Driver1 Ctl")];
+    if pages > 1 {
+        title.push(Span::styled(
+            format!("  ◀ {}/{} ▶", page + 1, pages),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+    if let Some(replay) = &app.replay {
+        let status = replay.status();
+        let state = match (status.finished, status.paused) {
+            (true, _) => "■",
+            (false, true) => "❚❚",
+            (false, false) => "▶",
+        };
+        title.push(Span::styled(
+            format!("  {} REPLAY x{} ({}/{})", state, status.speed, status.position, status.total),
+            Style::default().fg(Color::LightYellow),
+        ));
+    }
+    if let Some(recorder) = &app.recorder {
+        title.push(Span::styled(
+            format!("  ● REC {} ({})", recorder.path().display(), recorder.records()),
+            Style::default().fg(Color::LightRed),
+        ));
+    }
+
+    let paragraph = Paragraph::new(Spans::from(title))
         .style(Style::default().fg(Color::LightCyan))
+        .alignment(Alignment::Center);
+
+    if compact {
+        return paragraph;
+    }
+
+    paragraph.block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn draw_too_small<'a>(size: &Rect) -> Paragraph<'a> {
+    let text = format!(
+        "Terminal too small: {}x{}, need at least {}x{}",
+        size.width,
+        size.height,
+        MIN_WIDTH,
+        MIN_HEIGHT,
+    );
+
+    Paragraph::new(text)
+        .style(Style::default().fg(Color::LightRed))
         .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White))
-                .border_type(BorderType::Plain),
-        )
+        .wrap(Wrap { trim: true })
 }
 
 
@@ -505,12 +849,3 @@ fn styled_paragraph<'a, T>(text: T) -> Paragraph<'a>
                 .border_type(BorderType::Plain),
         )
 }
-
-fn check_size(rect: &Rect) {
-    if rect.width < 52 {
-        panic!("Require width >= 52, got: {}", rect.width);
-    }
-    if rect.height < 28 {
-        panic!("Require height >= 28, got: {}", rect.height);
-    }
-}
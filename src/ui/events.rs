@@ -6,35 +6,74 @@ use std::time::Duration;
 
 use crossterm::event;
 use log::error;
-use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::mpsc::Receiver;
 
 use crate::ui::key::UiKey;
 
 pub enum UiInputEvent {
     Input(UiKey),
+    Mouse(UiMouse),
     Tick,
 }
 
+/// Mouse events the UI handles, at terminal column and row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiMouse {
+    Click(u16, u16),
+    ScrollUp(u16, u16),
+    ScrollDown(u16, u16),
+}
+
+impl UiMouse {
+    fn from(event: event::MouseEvent) -> Option<Self> {
+        let (x, y) = (event.column, event.row);
+        match event.kind {
+            event::MouseEventKind::Down(event::MouseButton::Left) => Some(UiMouse::Click(x, y)),
+            event::MouseEventKind::ScrollUp => Some(UiMouse::ScrollUp(x, y)),
+            event::MouseEventKind::ScrollDown => Some(UiMouse::ScrollDown(x, y)),
+            _ => None,
+        }
+    }
+}
+
 pub struct UiEvents {
     rx: Receiver<UiInputEvent>,
-    _tx: Sender<UiInputEvent>,
     stopper: Arc<AtomicBool>,
     _tui_event_reader: JoinHandle<()>,
 }
 
 impl UiEvents {
     pub fn new(tick_rate: Duration) -> Self {
-        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let (event_tx, rx) = tokio::sync::mpsc::channel(1);
         let stopper = Arc::new(AtomicBool::new(false));
-        let event_tx = tx.clone();
         let event_stopper = stopper.clone();
 
+        // Exits on input errors, dropping the sender closes the channel.
         let t = thread::spawn(move || {
             loop {
-                if event::poll(tick_rate).unwrap() {
-                    if let event::Event::Key(key) = event::read().unwrap() {
-                        let key = UiKey::from(key);
-                        if let Err(err) = event_tx.blocking_send(UiInputEvent::Input(key)) {
+                let ready = match event::poll(tick_rate) {
+                    Ok(ready) => ready,
+                    Err(err) => {
+                        error!("could not poll terminal events: {}", err);
+                        break;
+                    }
+                };
+
+                if ready {
+                    let event = match event::read() {
+                        Ok(event) => event,
+                        Err(err) => {
+                            error!("could not read terminal event: {}", err);
+                            break;
+                        }
+                    };
+                    let input = match event {
+                        event::Event::Key(key) => Some(UiInputEvent::Input(UiKey::from(key))),
+                        event::Event::Mouse(mouse) => UiMouse::from(mouse).map(UiInputEvent::Mouse),
+                        _ => None,
+                    };
+                    if let Some(input) = input {
+                        if let Err(err) = event_tx.blocking_send(input) {
                             error!("err {}", err);
                         }
                     }
@@ -51,14 +90,14 @@ impl UiEvents {
 
         Self {
             rx,
-            _tx: tx,
             stopper,
             _tui_event_reader: t,
         }
     }
 
-    pub async fn next(&mut self) -> UiInputEvent {
-        self.rx.recv().await.unwrap_or(UiInputEvent::Tick)
+    /// Next input, `None` once the terminal can no longer be read.
+    pub async fn next(&mut self) -> Option<UiInputEvent> {
+        self.rx.recv().await
     }
 
     pub fn close(&mut self) {
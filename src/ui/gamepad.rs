@@ -0,0 +1,261 @@
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, BorderType, Paragraph};
+
+use crate::stick::{CtlInfo, CtlState};
+
+const GAMEPAD_WIDTH: usize = 48;
+const GAMEPAD_HEIGHT: usize = 11;
+const FLIGHT_WIDTH: usize = 48;
+const FLIGHT_HEIGHT: usize = 11;
+
+pub fn draw_layout<'a>(title: String, info: &CtlInfo, current: &CtlState) -> Paragraph<'a> {
+    let grid = if info.is_flightstick() {
+        flightstick(current)
+    } else {
+        gamepad(current)
+    };
+
+    Paragraph::new(grid.into_spans())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(title),
+        )
+}
+
+fn gamepad(c: &CtlState) -> Grid {
+    let mut g = Grid::new(GAMEPAD_WIDTH, GAMEPAD_HEIGHT);
+    let frame = Style::default().fg(Color::DarkGray);
+
+    g.text(1, 0, "LT", button(c.trigger_l.map(|v| v > 0.0)));
+    g.text(4, 0, &bar(c.trigger_l, 8), axis(c.trigger_l));
+    g.text(35, 0, &bar(c.trigger_r, 8), axis(c.trigger_r));
+    g.text(44, 0, "RT", button(c.trigger_r.map(|v| v > 0.0)));
+    g.text(1, 1, "[ LB ]", button(c.bumper_l));
+    g.text(41, 1, "[ RB ]", button(c.bumper_r));
+
+    g.frame(0, 2, GAMEPAD_WIDTH, 9, frame);
+
+    g.text(6, 3, "▲", button(or(c.up, c.pov_up)));
+    g.text(4, 4, "◀", button(or(c.left, c.pov_left)));
+    g.text(8, 4, "▶", button(or(c.right, c.pov_right)));
+    g.text(6, 5, "▼", button(or(c.down, c.pov_down)));
+
+    g.text(16, 3, "SEL", button(c.menu_l));
+    g.text(21, 3, "HOME", button(c.exit));
+    g.text(27, 3, "STA", button(c.menu_r));
+
+    g.text(38, 3, "(V)", button(c.action_v));
+    g.text(35, 4, "(H)", button(c.action_h));
+    g.text(41, 4, "(B)", button(c.action_b));
+    g.text(38, 5, "(A)", button(c.action_a));
+
+    g.stick(12, 6, c.joy_x, c.joy_y, c.joy);
+    g.stick(28, 6, c.cam_x, c.cam_y, c.cam);
+
+    g
+}
+
+fn flightstick(c: &CtlState) -> Grid {
+    let mut g = Grid::new(FLIGHT_WIDTH, FLIGHT_HEIGHT);
+    let frame = Style::default().fg(Color::DarkGray);
+    let label = Style::default().fg(Color::Gray);
+
+    g.frame(0, 0, 15, 9, frame);
+    g.text(1, 0, "Stick", label);
+    g.dot(1, 1, 13, 7, c.joy_x, c.joy_y, c.trigger);
+    g.text(0, 9, "Z", label);
+    g.text(2, 9, &centered_bar(c.joy_z, 13), axis(c.joy_z));
+    g.text(0, 10, "R", label);
+    g.text(2, 10, &centered_bar(c.rudder, 13), axis(c.rudder));
+
+    g.text(17, 0, "POV", label);
+    g.hat(17, 1, [c.pov_up, c.pov_down, c.pov_left, c.pov_right]);
+    g.text(17, 5, "HAT", label);
+    g.hat(17, 6, [c.hat_up, c.hat_down, c.hat_left, c.hat_right]);
+
+    g.text(23, 0, "[TRG]", button(c.trigger));
+    g.text(29, 0, "[THB]", button(c.action_m));
+    g.text(35, 0, "[BMP]", button(c.bumper_r));
+    g.text(23, 1, "[ L ]", button(c.action_l));
+    g.text(29, 1, "[ R ]", button(c.action_r));
+    g.text(35, 1, "[PNK]", button(c.pinky));
+
+    for n in 1..=16 {
+        let i = (n - 1) as usize;
+        let x = 23 + (i % 6) * 3;
+        let y = 3 + i / 6;
+        let pressed = c.number.get(&n).cloned();
+        g.text(x, y, &format!("{:02}", n), button(pressed));
+    }
+
+    g.text(43, 0, "THR", label);
+    g.vertical_bar(44, 1, 9, c.throttle);
+
+    g
+}
+
+fn or(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(false) || b.unwrap_or(false)),
+    }
+}
+
+fn button(pressed: Option<bool>) -> Style {
+    match pressed {
+        Some(true) => Style::default()
+            .fg(Color::Black)
+            .bg(Color::LightCyan)
+            .add_modifier(Modifier::BOLD),
+        Some(false) => Style::default().fg(Color::Gray),
+        None => Style::default().fg(Color::DarkGray),
+    }
+}
+
+fn axis(value: Option<f64>) -> Style {
+    match value {
+        Some(_) => Style::default().fg(Color::LightCyan),
+        None => Style::default().fg(Color::DarkGray),
+    }
+}
+
+/// Fill bar for 0.0..=1.0 values.
+fn bar(value: Option<f64>, width: usize) -> String {
+    let value = value.unwrap_or(0.0).clamp(0.0, 1.0);
+    let filled = (value * width as f64).round() as usize;
+    "█".repeat(filled) + &"░".repeat(width - filled)
+}
+
+/// Marker bar for -1.0..=1.0 values.
+fn centered_bar(value: Option<f64>, width: usize) -> String {
+    let center = width / 2;
+    let position = value
+        .map(|v| ((v.clamp(-1.0, 1.0) + 1.0) / 2.0 * (width - 1) as f64).round() as usize)
+        .unwrap_or(center);
+
+    (0..width)
+        .map(|i| match i {
+            i if i == position => '█',
+            i if i == center => '┼',
+            _ => '─',
+        })
+        .collect()
+}
+
+/// A fixed size character canvas, converted to styled lines for rendering.
+struct Grid {
+    cells: Vec<Vec<(char, Style)>>,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            cells: vec![vec![(' ', Style::default()); width]; height],
+        }
+    }
+
+    fn put(&mut self, x: usize, y: usize, c: char, style: Style) {
+        if let Some(cell) = self.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+            *cell = (c, style);
+        }
+    }
+
+    fn text(&mut self, x: usize, y: usize, text: &str, style: Style) {
+        for (i, c) in text.chars().enumerate() {
+            self.put(x + i, y, c, style);
+        }
+    }
+
+    fn frame(&mut self, x: usize, y: usize, width: usize, height: usize, style: Style) {
+        for i in 1..width - 1 {
+            self.put(x + i, y, '─', style);
+            self.put(x + i, y + height - 1, '─', style);
+        }
+        for j in 1..height - 1 {
+            self.put(x, y + j, '│', style);
+            self.put(x + width - 1, y + j, '│', style);
+        }
+        self.put(x, y, '╭', style);
+        self.put(x + width - 1, y, '╮', style);
+        self.put(x, y + height - 1, '╰', style);
+        self.put(x + width - 1, y + height - 1, '╯', style);
+    }
+
+    /// Position of an `x`/`y` pair inside the given area.
+    #[allow(clippy::too_many_arguments)]
+    fn dot(&mut self,
+           x: usize,
+           y: usize,
+           width: usize,
+           height: usize,
+           value_x: Option<f64>,
+           value_y: Option<f64>,
+           pressed: Option<bool>) {
+        let scale = |v: Option<f64>, size: usize| {
+            ((v.unwrap_or(0.0).clamp(-1.0, 1.0) + 1.0) / 2.0 * (size - 1) as f64).round() as usize
+        };
+        let dim = Style::default().fg(Color::DarkGray);
+        self.put(x + width / 2, y + height / 2, '┼', dim);
+
+        let style = match pressed {
+            Some(true) => button(pressed),
+            _ => axis(value_x.or(value_y)),
+        };
+        self.put(x + scale(value_x, width), y + scale(value_y, height), '●', style);
+    }
+
+    fn stick(&mut self,
+             x: usize,
+             y: usize,
+             value_x: Option<f64>,
+             value_y: Option<f64>,
+             pressed: Option<bool>) {
+        let style = match pressed {
+            Some(true) => button(pressed),
+            _ => Style::default().fg(Color::Gray),
+        };
+        self.frame(x, y, 7, 3, style);
+        self.dot(x + 1, y + 1, 5, 1, value_x, value_y, pressed);
+    }
+
+    /// Up, down, left, right.
+    fn hat(&mut self, x: usize, y: usize, hat: [Option<bool>; 4]) {
+        self.text(x + 1, y, "▲", button(hat[0]));
+        self.text(x, y + 1, "◀", button(hat[2]));
+        self.text(x + 1, y + 1, "·", Style::default().fg(Color::DarkGray));
+        self.text(x + 2, y + 1, "▶", button(hat[3]));
+        self.text(x + 1, y + 2, "▼", button(hat[1]));
+    }
+
+    fn vertical_bar(&mut self, x: usize, y: usize, height: usize, value: Option<f64>) {
+        let filled = (value.unwrap_or(0.0).clamp(0.0, 1.0) * height as f64).round() as usize;
+        for i in 0..height {
+            let c = if height - i <= filled { '█' } else { '░' };
+            self.put(x, y + i, c, axis(value));
+            self.put(x + 1, y + i, c, axis(value));
+        }
+    }
+
+    fn into_spans<'a>(self) -> Vec<Spans<'a>> {
+        self.cells
+            .into_iter()
+            .map(|row| {
+                let mut spans: Vec<Span> = Vec::new();
+                let mut run = String::new();
+                let mut run_style = Style::default();
+                for (c, style) in row {
+                    if style != run_style && !run.is_empty() {
+                        spans.push(Span::styled(std::mem::take(&mut run), run_style));
+                    }
+                    run_style = style;
+                    run.push(c);
+                }
+                spans.push(Span::styled(run, run_style));
+                Spans::from(spans)
+            })
+            .collect()
+    }
+}
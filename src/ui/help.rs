@@ -0,0 +1,54 @@
+use tui::layout::{Constraint, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::widgets::{Block, Borders, BorderType, Cell, Row, Table};
+
+use crate::ui::action::UiActions;
+
+const HELP_WIDTH: u16 = 82;
+
+/// Centered over `size`, shrunk to fit small terminals.
+pub fn help_area(size: Rect, actions: &UiActions) -> Rect {
+    let width = HELP_WIDTH.min(size.width);
+    let height = (actions.bindings().len() as u16 + 4).min(size.height);
+
+    Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+pub fn draw_help<'a>(actions: &UiActions) -> Table<'a> {
+    let rows = actions
+        .bindings()
+        .iter()
+        .map(|(action, keys)| {
+            let keys = keys
+                .iter()
+                .map(|key| key.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            Row::new(vec![
+                Cell::from(keys).style(Style::default().add_modifier(Modifier::BOLD)),
+                Cell::from(action.description()),
+                Cell::from(action.name()).style(Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let header = Row::new(vec!["Keys", "Action", "Config name"])
+        .style(Style::default().fg(Color::LightCyan))
+        .bottom_margin(1);
+
+    Table::new(rows)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title("Help"),
+        )
+        .widths(&[Constraint::Length(18), Constraint::Length(44), Constraint::Length(16)])
+        .column_spacing(1)
+}
@@ -2,6 +2,7 @@
 
 use crossterm::event;
 use std::fmt;
+use std::str::FromStr;
 
 /// Represents an key.
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
@@ -10,6 +11,8 @@ pub enum UiKey {
     Enter,
     /// Tabulation key
     Tab,
+    /// Shift + Tabulation key
+    BackTab,
     /// Backspace key
     Backspace,
     /// Escape key
@@ -106,22 +109,102 @@ impl fmt::Display for UiKey {
             UiKey::Alt(c) => write!(f, "<Alt+{}>", c),
             UiKey::Ctrl(c) => write!(f, "<Ctrl+{}>", c),
             UiKey::Char(c) => write!(f, "{}", c),
-            UiKey::Left | UiKey::Right | UiKey::Up | UiKey::Down => write!(f, "<?? Arrow Key>"),
-            UiKey::Enter
-            | UiKey::Tab
-            | UiKey::Backspace
-            | UiKey::Esc
-            | UiKey::Ins
-            | UiKey::Delete
-            | UiKey::Home
-            | UiKey::End
-            | UiKey::PageUp
-            | UiKey::PageDown => write!(f, "<??>"),
-            _ => write!(f, "??"),
+            UiKey::Enter => write!(f, "<Enter>"),
+            UiKey::Tab => write!(f, "<Tab>"),
+            UiKey::BackTab => write!(f, "<BackTab>"),
+            UiKey::Backspace => write!(f, "<Backspace>"),
+            UiKey::Esc => write!(f, "<Esc>"),
+            UiKey::Left => write!(f, "<Left>"),
+            UiKey::Right => write!(f, "<Right>"),
+            UiKey::Up => write!(f, "<Up>"),
+            UiKey::Down => write!(f, "<Down>"),
+            UiKey::Ins => write!(f, "<Ins>"),
+            UiKey::Delete => write!(f, "<Delete>"),
+            UiKey::Home => write!(f, "<Home>"),
+            UiKey::End => write!(f, "<End>"),
+            UiKey::PageUp => write!(f, "<PageUp>"),
+            UiKey::PageDown => write!(f, "<PageDown>"),
+            UiKey::F0 => write!(f, "<F0>"),
+            UiKey::F1 => write!(f, "<F1>"),
+            UiKey::F2 => write!(f, "<F2>"),
+            UiKey::F3 => write!(f, "<F3>"),
+            UiKey::F4 => write!(f, "<F4>"),
+            UiKey::F5 => write!(f, "<F5>"),
+            UiKey::F6 => write!(f, "<F6>"),
+            UiKey::F7 => write!(f, "<F7>"),
+            UiKey::F8 => write!(f, "<F8>"),
+            UiKey::F9 => write!(f, "<F9>"),
+            UiKey::F10 => write!(f, "<F10>"),
+            UiKey::F11 => write!(f, "<F11>"),
+            UiKey::F12 => write!(f, "<F12>"),
+            UiKey::Unknown => write!(f, "??"),
         }
     }
 }
 
+/// Parses the `Display` format: a single character such as `q`, or a name in
+/// angle brackets such as `<Space>`, `<Ctrl+c>`, `<Alt+x>`, `<PageUp>` or `<F5>`.
+impl FromStr for UiKey {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(UiKey::Char(c));
+        }
+
+        let name = s
+            .strip_prefix('<')
+            .and_then(|s| s.strip_suffix('>'))
+            .ok_or_else(|| eyre::eyre!("invalid key {}, expected a character or <Name>", s))?;
+
+        let single = |rest: &str| -> Option<char> {
+            if rest.eq_ignore_ascii_case("space") {
+                return Some(' ');
+            }
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
+            }
+        };
+
+        if let Some((modifier, rest)) = name.split_once('+') {
+            let c = single(rest).ok_or_else(|| eyre::eyre!("invalid key {}", s))?;
+            return match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => Ok(UiKey::Ctrl(c)),
+                "alt" => Ok(UiKey::Alt(c)),
+                _ => eyre::bail!("invalid modifier {} in key {}", modifier, s),
+            };
+        }
+
+        let key = match name.to_ascii_lowercase().as_str() {
+            "space" => UiKey::Char(' '),
+            "enter" => UiKey::Enter,
+            "tab" => UiKey::Tab,
+            "backtab" => UiKey::BackTab,
+            "backspace" => UiKey::Backspace,
+            "esc" => UiKey::Esc,
+            "left" => UiKey::Left,
+            "right" => UiKey::Right,
+            "up" => UiKey::Up,
+            "down" => UiKey::Down,
+            "ins" => UiKey::Ins,
+            "delete" => UiKey::Delete,
+            "home" => UiKey::Home,
+            "end" => UiKey::End,
+            "pageup" => UiKey::PageUp,
+            "pagedown" => UiKey::PageDown,
+            f => match f.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n) if n <= 12 => UiKey::from_f(n),
+                _ => eyre::bail!("unknown key {}", s),
+            },
+        };
+
+        Ok(key)
+    }
+}
+
 impl From<event::KeyEvent> for UiKey {
     fn from(key_event: event::KeyEvent) -> Self {
         match key_event {
@@ -185,6 +268,10 @@ impl From<event::KeyEvent> for UiKey {
                 code: event::KeyCode::Tab,
                 ..
             } => UiKey::Tab,
+            event::KeyEvent {
+                code: event::KeyCode::BackTab,
+                ..
+            } => UiKey::BackTab,
 
             // First check for char + modifier
             event::KeyEvent {
@@ -204,4 +291,59 @@ impl From<event::KeyEvent> for UiKey {
             _ => UiKey::Unknown,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_what_it_displays() {
+        let keys = [
+            UiKey::Char('q'),
+            UiKey::Char(' '),
+            UiKey::Char('<'),
+            UiKey::Char('+'),
+            UiKey::Ctrl('c'),
+            UiKey::Ctrl(' '),
+            UiKey::Alt('x'),
+            UiKey::Alt(' '),
+            UiKey::Enter,
+            UiKey::Tab,
+            UiKey::BackTab,
+            UiKey::Backspace,
+            UiKey::Esc,
+            UiKey::Left,
+            UiKey::Right,
+            UiKey::Up,
+            UiKey::Down,
+            UiKey::Ins,
+            UiKey::Delete,
+            UiKey::Home,
+            UiKey::End,
+            UiKey::PageUp,
+            UiKey::PageDown,
+            UiKey::F0,
+            UiKey::F5,
+            UiKey::F12,
+        ];
+
+        for key in keys {
+            assert_eq!(key.to_string().parse::<UiKey>().unwrap(), key, "{}", key);
+        }
+    }
+
+    #[test]
+    fn parses_names_ignoring_case() {
+        assert_eq!("<ctrl+c>".parse::<UiKey>().unwrap(), UiKey::Ctrl('c'));
+        assert_eq!("<ALT+space>".parse::<UiKey>().unwrap(), UiKey::Alt(' '));
+        assert_eq!("<pageup>".parse::<UiKey>().unwrap(), UiKey::PageUp);
+        assert_eq!("<f5>".parse::<UiKey>().unwrap(), UiKey::F5);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        for key in ["", "ab", "<>", "<F13>", "<Meta+x>", "<Ctrl+ab>", "<Nope>", "<Space"] {
+            assert!(key.parse::<UiKey>().is_err(), "{}", key);
+        }
+    }
+}
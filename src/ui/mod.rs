@@ -2,3 +2,11 @@ pub mod key;
 pub mod events;
 pub mod action;
 pub mod draw;
+pub mod chart;
+pub mod sticks;
+pub mod gamepad;
+pub mod raw;
+pub mod help;
+pub mod diagnostics;
+pub mod rumble;
+pub mod terminal;
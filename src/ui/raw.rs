@@ -0,0 +1,66 @@
+use tui::layout::Constraint;
+use tui::style::{Color, Style};
+use tui::widgets::{Block, Borders, BorderType, Cell, Row, Table};
+
+use crate::app::CtlHistory;
+
+/// Most recent raw events next to the events they were mapped to, newest at
+/// the bottom. The mapped event is shown on the row of the raw event that
+/// completed it.
+pub fn draw_raw<'a>(title: String, history: Option<&CtlHistory>, height: u16) -> Table<'a> {
+    let raw_style = Style::default().fg(Color::Gray);
+    let event_style = Style::default().fg(Color::LightCyan);
+
+    // Borders and header.
+    let visible = height.saturating_sub(3) as usize;
+    let mut rows = Vec::with_capacity(visible);
+    for ctl_event in history.iter().flat_map(|history| history.events.iter().rev()) {
+        if rows.len() >= visible {
+            break;
+        }
+
+        let event = Cell::from(ctl_event.triggering_event.to_string()).style(event_style);
+        if ctl_event.raw.is_empty() {
+            rows.push(Row::new(vec![Cell::from("-"), Cell::from(""), Cell::from(""), Cell::from(""), event]));
+            continue;
+        }
+
+        let mut event = Some(event);
+        for raw in ctl_event.raw.iter().rev() {
+            let time = raw.time.as_secs() % 1000;
+            let micros = raw.time.subsec_micros();
+            rows.push(
+                Row::new(vec![
+                    Cell::from(format!("{:>3}.{:06}", time, micros)),
+                    Cell::from(raw.kind_name()),
+                    Cell::from(raw.code_name()),
+                    Cell::from(raw.value.to_string()),
+                    event.take().unwrap_or_else(|| Cell::from("")),
+                ])
+                .style(raw_style),
+            );
+        }
+    }
+    rows.truncate(visible);
+    rows.reverse();
+
+    let header = Row::new(vec!["Kernel time", "Type", "Code", "Value", "Event"])
+        .style(Style::default().fg(Color::LightCyan));
+
+    Table::new(rows)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(title),
+        )
+        .widths(&[
+            Constraint::Length(11),
+            Constraint::Length(7),
+            Constraint::Length(20),
+            Constraint::Length(7),
+            Constraint::Length(24),
+        ])
+        .column_spacing(1)
+}
@@ -0,0 +1,70 @@
+use tui::layout::Rect;
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, BorderType, Paragraph};
+
+use crate::app::rumble::{RumbleField, RumbleKind, RumbleTest};
+
+const RUMBLE_WIDTH: u16 = 34;
+const RUMBLE_HEIGHT: u16 = RumbleField::ALL.len() as u16 + 2;
+const BAR_WIDTH: usize = 10;
+
+/// Bottom right corner of `chunk`, shrunk to fit small terminals.
+pub fn rumble_area(chunk: Rect) -> Rect {
+    let width = RUMBLE_WIDTH.min(chunk.width);
+    let height = RUMBLE_HEIGHT.min(chunk.height);
+
+    Rect::new(
+        chunk.x + chunk.width - width,
+        chunk.y + chunk.height - height,
+        width,
+        height,
+    )
+}
+
+/// `target` is what the rumble is sent to, for the title.
+pub fn draw_rumble<'a>(test: &RumbleTest, target: String) -> Paragraph<'a> {
+    let lines = RumbleField::ALL
+        .iter()
+        .map(|field| {
+            let selected = *field == test.field;
+            let value = match field {
+                RumbleField::Left => power(test.left),
+                RumbleField::Right => power(test.right),
+                RumbleField::Duration => format!("{} ms", test.duration.as_millis()),
+                RumbleField::Pattern => test.kind.name().to_string(),
+                RumbleField::Period => format!("{} ms", test.period.as_millis()),
+            };
+
+            let style = match (selected, field, test.kind) {
+                (true, _, _) => Style::default().fg(Color::LightCyan).add_modifier(Modifier::BOLD),
+                (_, RumbleField::Period, RumbleKind::Constant) => Style::default().fg(Color::DarkGray),
+                _ => Style::default(),
+            };
+
+            Spans::from(vec![
+                Span::styled(if selected { "> " } else { "  " }, style),
+                Span::styled(format!("{:<9}", field.name()), style),
+                Span::styled(value, style),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(format!("Rumble {}", target)),
+        )
+}
+
+fn power(value: f32) -> String {
+    let filled = (value * BAR_WIDTH as f32).round() as usize;
+    format!(
+        "{}{} {:.1}",
+        "█".repeat(filled),
+        "░".repeat(BAR_WIDTH - filled.min(BAR_WIDTH)),
+        value,
+    )
+}
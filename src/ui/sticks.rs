@@ -0,0 +1,167 @@
+use std::time::{Duration, Instant};
+
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Style};
+use tui::symbols::Marker;
+use tui::widgets::{Block, Borders, BorderType, Paragraph};
+use tui::widgets::canvas::{Canvas, Line, Points};
+
+use crate::app::AxisSamples;
+
+const TRAIL: Duration = Duration::from_secs(2);
+
+static PAIRS: [(&str, &str, &str); 4] = [
+    ("Joy", "Joy X", "Joy Y"),
+    ("Cam", "Cam X", "Cam Y"),
+    ("Mouse", "Mouse X", "Mouse Y"),
+    ("Action Wheel", "Action Wheel X", "Action Wheel Y"),
+];
+
+pub fn draw_sticks<B>(rect: &mut Frame<B>,
+                      area: Rect,
+                      title: String,
+                      axes: Option<&Vec<AxisSamples>>,
+                      deadzone: f64,
+                      now: Instant)
+    where B: Backend {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Plain)
+        .title(title);
+    let inner = block.inner(area);
+    rect.render_widget(block, area);
+
+    let axes = axes.map(|axes| axes.as_slice()).unwrap_or_default();
+    let find = |name: &str| axes.iter().find(|axis| axis.name == name);
+
+    let sticks = PAIRS
+        .iter()
+        .filter_map(|(name, x, y)| match (find(x), find(y)) {
+            (None, None) => None,
+            (x, y) => Some((*name, x, y)),
+        })
+        .collect::<Vec<_>>();
+
+    if sticks.is_empty() {
+        let empty = Paragraph::new("No stick moved yet")
+            .style(Style::default().fg(Color::DarkGray));
+        rect.render_widget(empty, inner);
+        return;
+    }
+
+    let constraints = vec![Constraint::Ratio(1, sticks.len() as u32); sticks.len()];
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(&constraints[..])
+        .split(inner);
+
+    for ((name, x, y), chunk) in sticks.into_iter().zip(chunks) {
+        draw_stick(rect, chunk, name, x, y, deadzone, now);
+    }
+}
+
+fn draw_stick<B>(rect: &mut Frame<B>,
+                 area: Rect,
+                 name: &str,
+                 x: Option<&AxisSamples>,
+                 y: Option<&AxisSamples>,
+                 deadzone: f64,
+                 now: Instant)
+    where B: Backend {
+    let trail = trail(x, y, now);
+    let (cx, cy) = trail.last().cloned().unwrap_or((0.0, 0.0));
+    let radius = (cx * cx + cy * cy).sqrt();
+
+    // Screen y grows upwards, stick y grows downwards.
+    let trail = trail.into_iter().map(|(x, y)| (x, -y)).collect::<Vec<_>>();
+    let current = [(cx, -cy)];
+    let unit = circle(1.0);
+    let dead = circle(deadzone);
+
+    // Braille dots are about square, keep the unit circle round.
+    let ratio = if area.height == 0 {
+        1.0
+    } else {
+        f64::from(area.width) / (2.0 * f64::from(area.height))
+    };
+    let (x_bound, y_bound) = if ratio >= 1.0 {
+        (1.1 * ratio, 1.1)
+    } else {
+        (1.1, 1.1 / ratio)
+    };
+
+    let title = format!("{} x {:.2} y {:.2} r {:.2} dz {:.2}", name, cx, cy, radius, deadzone);
+
+    let canvas = Canvas::default()
+        .block(Block::default().title(title))
+        .marker(Marker::Braille)
+        .x_bounds([-x_bound, x_bound])
+        .y_bounds([-y_bound, y_bound])
+        .paint(move |ctx| {
+            ctx.draw(&Line { x1: -1.0, y1: 0.0, x2: 1.0, y2: 0.0, color: Color::DarkGray });
+            ctx.draw(&Line { x1: 0.0, y1: -1.0, x2: 0.0, y2: 1.0, color: Color::DarkGray });
+            ctx.draw(&Points { coords: &unit, color: Color::Gray });
+            ctx.draw(&Points { coords: &dead, color: Color::Yellow });
+            ctx.layer();
+            ctx.draw(&Points { coords: &trail, color: Color::Cyan });
+            ctx.layer();
+            ctx.draw(&Points { coords: &current, color: Color::LightRed });
+        });
+
+    rect.render_widget(canvas, area);
+}
+
+fn circle(radius: f64) -> Vec<(f64, f64)> {
+    if radius <= 0.0 {
+        return vec![];
+    }
+
+    let steps = 256;
+    (0..steps)
+        .map(|i| {
+            let angle = std::f64::consts::TAU * f64::from(i) / f64::from(steps);
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect()
+}
+
+/// Merges the recent samples of both axes into positions, oldest first.
+fn trail(x: Option<&AxisSamples>, y: Option<&AxisSamples>, now: Instant) -> Vec<(f64, f64)> {
+    let since = now.checked_sub(TRAIL).unwrap_or(now);
+
+    let mut changes = Vec::with_capacity(128);
+    let mut x_value = 0.0;
+    let mut y_value = 0.0;
+
+    if let Some(x) = x {
+        for (at, value) in x.samples.iter() {
+            if *at < since {
+                x_value = *value;
+            } else {
+                changes.push((*at, Some(*value), None));
+            }
+        }
+    }
+    if let Some(y) = y {
+        for (at, value) in y.samples.iter() {
+            if *at < since {
+                y_value = *value;
+            } else {
+                changes.push((*at, None, Some(*value)));
+            }
+        }
+    }
+    changes.sort_by_key(|(at, _, _)| *at);
+
+    let mut points = Vec::with_capacity(changes.len() + 1);
+    points.push((x_value, y_value));
+    for (_, x, y) in changes {
+        x_value = x.unwrap_or(x_value);
+        y_value = y.unwrap_or(y_value);
+        points.push((x_value, y_value));
+    }
+
+    points
+}
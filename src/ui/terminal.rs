@@ -0,0 +1,98 @@
+use std::io::stdout;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+use crossterm::cursor::Show;
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use log::{info, warn};
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static PANIC_HOOK: Once = Once::new();
+
+/// Puts the terminal in raw mode on the alternate screen with mouse capture,
+/// and restores it when dropped or on `restore()`.
+///
+/// A panic on any thread while the guard is active restores the terminal,
+/// reports the panic and exits: the UI can not go on in a restored terminal.
+pub struct TerminalGuard(());
+
+impl TerminalGuard {
+    pub fn enter() -> eyre::Result<Self> {
+        PANIC_HOOK.call_once(|| {
+            let previous = panic::take_hook();
+            panic::set_hook(Box::new(move |panic_info| {
+                let active = restore();
+                previous(panic_info);
+                if active {
+                    std::process::exit(101);
+                }
+            }));
+        });
+
+        enable_raw_mode()?;
+        ACTIVE.store(true, Ordering::Release);
+        // From here on, an early return drops the guard and restores.
+        let guard = Self(());
+        execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Leaves raw mode, mouse capture and the alternate screen and shows the
+/// cursor, if a `TerminalGuard` is active. Errors are ignored, there is
+/// nothing left to report them on. Returns whether a guard was active.
+pub fn restore() -> bool {
+    let active = ACTIVE.swap(false, Ordering::AcqRel);
+    if active {
+        let _ = disable_raw_mode();
+        let _ = execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen, Show);
+    }
+    active
+}
+
+/// Resolves on SIGINT, SIGTERM or SIGHUP (Ctrl-C elsewhere).
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::SignalKind;
+
+        tokio::select! {
+            _ = unix_signal(SignalKind::interrupt()) => {}
+            _ = unix_signal(SignalKind::terminate()) => {}
+            _ = unix_signal(SignalKind::hangup()) => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            warn!("could not listen to Ctrl-C: {}", err);
+            std::future::pending::<()>().await;
+        }
+    }
+
+    info!("shutdown signal received");
+}
+
+#[cfg(unix)]
+async fn unix_signal(kind: tokio::signal::unix::SignalKind) {
+    match tokio::signal::unix::signal(kind) {
+        Ok(mut stream) => {
+            stream.recv().await;
+        }
+        Err(err) => {
+            warn!("could not listen to signal {:?}: {}", kind, err);
+            std::future::pending::<()>().await;
+        }
+    }
+}
@@ -12,11 +12,12 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::future::Future;
+use std::io;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use crate::Event;
+use crate::{Effect, EffectId, EffectSupport, Event, RawEvent};
 
 #[repr(i8)]
 enum Btn {
@@ -269,6 +270,24 @@ impl Remap {
     }
 }
 
+/// Raw range of an analog axis of a [`Controller`], as reported by the OS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AxisInfo {
+    /// Id of the event the axis produces, see
+    /// [`Event::to_id()`](crate::Event::to_id).
+    pub event: u8,
+    /// Smallest raw value.
+    pub minimum: i32,
+    /// Largest raw value.
+    pub maximum: i32,
+    /// Noise filtered out by the OS, in raw units.
+    pub fuzz: i32,
+    /// Dead zone around the center, in raw units.
+    pub flat: i32,
+    /// Raw units per millimeter (or per radian), 0 if unknown.
+    pub resolution: i32,
+}
+
 /// A gamepad, flightstick, or other controller.
 pub struct Controller {
     // Shared remapping.
@@ -318,6 +337,64 @@ impl Controller {
         self.raw.name()
     }
 
+    /// Get the type of this Pad from the remapping database: `'x'` (Xbox),
+    /// `'p'` (PlayStation), `'n'` (Nintendo), `'g'` (other gamepad), `'f'`
+    /// (flightstick), or `'w'` if the Pad is not in the database.
+    pub fn kind(&self) -> char {
+        self.remap.type_
+    }
+
+    /// Get the device path this Pad was opened from (for example
+    /// `/dev/input/event5` on Linux), if the platform has one.
+    pub fn path(&self) -> Option<&str> {
+        self.raw.path()
+    }
+
+    /// Get the serial number of this Pad (its Bluetooth address for
+    /// wireless ones on Linux), if it reports one.  Unlike
+    /// [`path()`](Self::path), it stays the same across reconnects.
+    pub fn serial(&self) -> Option<&str> {
+        self.raw.serial()
+    }
+
+    /// Get where this Pad is plugged in (for example
+    /// `usb-0000:00:14.0-2/input0` on Linux), if it reports it.  Stays the
+    /// same while the Pad is plugged into the same port.
+    pub fn location(&self) -> Option<&str> {
+        self.raw.location()
+    }
+
+    /// Get the raw ranges of the analog axes of this Pad (empty on platforms
+    /// that don't report them).  Each axis is normalized with its own range.
+    pub fn axes(&self) -> Vec<AxisInfo> {
+        self.raw
+            .axes()
+            .into_iter()
+            .map(|mut axis| {
+                if let Some(map) = self.remap.maps.get(&axis.event) {
+                    axis.event = map.out;
+                }
+                axis
+            })
+            .collect()
+    }
+
+    /// Start or stop keeping the raw events this Pad reads (evdev events on
+    /// Linux), to be taken with [`raw_events()`](Self::raw_events).  Off by
+    /// default, and ignored on platforms without raw events.
+    pub fn keep_raw_events(&mut self, keep: bool) {
+        self.raw.keep_raw_events(keep);
+    }
+
+    /// Take the raw events read since the last call, oldest first.  Only
+    /// the most recent ones are kept if this isn't called often enough.
+    ///
+    /// Raw events are read as the Pad is polled, so the ones taken right
+    /// after an [`Event`] include the ones it was made from.
+    pub fn raw_events(&mut self) -> Vec<RawEvent> {
+        self.raw.raw_events()
+    }
+
     /// Turn on/off haptic force feedback.
     ///
     /// Takes either an `f32` for mono power or `(f32, f32)` for directional
@@ -330,6 +407,43 @@ impl Controller {
         self.raw.rumble(power.left(), power.right());
     }
 
+    /// Get the force feedback effects this Pad can play (empty on platforms
+    /// without force feedback support).
+    pub fn effects(&self) -> EffectSupport {
+        self.raw.effects()
+    }
+
+    /// Upload a force feedback effect to this Pad, to be played with
+    /// [`play()`](Self::play).
+    ///
+    /// Fails with [`io::ErrorKind::Unsupported`] if the platform has no
+    /// force feedback support, or with the OS error if the Pad rejects the
+    /// effect (for example, when all its effect slots are taken).
+    pub fn upload(&mut self, effect: &Effect) -> io::Result<EffectId> {
+        self.raw.upload(effect, -1).map(EffectId)
+    }
+
+    /// Replace an uploaded effect, taking effect immediately if it's
+    /// playing.
+    pub fn update(&mut self, id: EffectId, effect: &Effect) -> io::Result<()> {
+        self.raw.upload(effect, id.0).map(|_| ())
+    }
+
+    /// Play an uploaded effect `count` times in a row.
+    pub fn play(&mut self, id: EffectId, count: u16) -> io::Result<()> {
+        self.raw.play(id.0, count.max(1))
+    }
+
+    /// Stop playing an uploaded effect.
+    pub fn stop(&mut self, id: EffectId) -> io::Result<()> {
+        self.raw.play(id.0, 0)
+    }
+
+    /// Remove an uploaded effect, freeing its slot.
+    pub fn erase(&mut self, id: EffectId) -> io::Result<()> {
+        self.raw.erase(id.0)
+    }
+
     fn button(&mut self, b: Btn, f: fn(bool) -> Event, p: bool) -> Poll<Event> {
         let b = 1u128 << b as i8;
         if (self.btns & b != 0) == p {
@@ -371,7 +485,7 @@ impl Controller {
                     - 1.0)
                     .clamp(-1.0, 1.0)
             } else {
-                self.raw.axis(v).clamp(-1.0, 1.0)
+                self.raw.axis(ev, v).clamp(-1.0, 1.0)
             };
             if !map.deadzone.is_nan() && v.abs() <= map.deadzone {
                 0.0
@@ -379,7 +493,7 @@ impl Controller {
                 v
             }
         } else {
-            self.raw.axis(v).clamp(-1.0, 1.0)
+            self.raw.axis(ev, v).clamp(-1.0, 1.0)
         };
         let axis = a as usize;
         if self.axis[axis] == v {
@@ -404,7 +518,7 @@ impl Controller {
                 ((v - f64::from(map.min)) / f64::from(map.max - map.min))
                     .clamp(0.0, 1.0)
             } else {
-                self.raw.pressure(v).clamp(0.0, 1.0)
+                self.raw.pressure(ev, v).clamp(0.0, 1.0)
             };
             if !map.deadzone.is_nan() && v <= map.deadzone {
                 0.0
@@ -412,7 +526,7 @@ impl Controller {
                 v
             }
         } else {
-            self.raw.pressure(v).clamp(0.0, 1.0)
+            self.raw.pressure(ev, v).clamp(0.0, 1.0)
         };
         let axis = a as usize;
         if self.axis[axis] == v {
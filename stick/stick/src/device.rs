@@ -0,0 +1,52 @@
+// Stick
+// Copyright © 2017-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your option (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).  This file may not be copied,
+// modified, or distributed except according to those terms.
+
+use std::io;
+
+/// What happened when a [`Listener`](crate::Listener) tried a device node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceNode {
+    /// Path of the device node, like `/dev/input/event5`.
+    pub path: String,
+    /// How the node could be opened, or the OS error (errno) if it couldn't
+    /// be opened or set up.
+    pub access: Result<DeviceAccess, i32>,
+    /// Whether the device looks like a game controller, the same check that
+    /// decides whether a listener yields it.  `None` if it couldn't be
+    /// opened.
+    pub controller: Option<bool>,
+}
+
+/// How a device node could be opened.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceAccess {
+    /// Events can be read and force feedback sent.
+    ReadWrite,
+    /// Events can be read, but not force feedback sent.
+    ReadOnly,
+    /// Force feedback can be sent, but no events read.
+    WriteOnly,
+}
+
+/// Try every device node the way a [`Listener`](crate::Listener) does,
+/// without keeping any open, and return what happened to each.
+///
+/// Fails if the device directory can't be read, or with
+/// [`io::ErrorKind::Unsupported`] on platforms without device nodes.
+pub fn probe_devices() -> io::Result<Vec<DeviceNode>> {
+    crate::raw::GLOBAL.with(|g| g.probe_devices())
+}
+
+/// What happened the last time each device node was tried, by a
+/// [`Listener`](crate::Listener) or [`probe_devices()`], in this process.
+pub fn tried_devices() -> Vec<DeviceNode> {
+    crate::raw::GLOBAL.with(|g| g.tried_devices())
+}
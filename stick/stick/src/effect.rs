@@ -0,0 +1,201 @@
+// Stick
+// Copyright © 2017-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your option (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).  This file may not be copied,
+// modified, or distributed except according to those terms.
+
+use std::time::Duration;
+
+/// A force feedback effect, uploaded with
+/// [`Controller::upload()`](crate::Controller::upload).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Effect {
+    /// What the effect does.
+    pub kind: EffectKind,
+    /// Direction the force comes from, in turns: 0.0 is down, 0.25 left,
+    /// 0.5 up and 0.75 right.
+    pub direction: f32,
+    /// How long the effect plays, zero for as long as the device allows.
+    pub length: Duration,
+    /// How long to wait before playing the effect.
+    pub delay: Duration,
+}
+
+impl Effect {
+    /// An effect of `kind` from below, playing for `length` without delay.
+    pub fn new(kind: EffectKind, length: Duration) -> Self {
+        Self {
+            kind,
+            direction: 0.0,
+            length,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+/// The kinds of force feedback effects.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EffectKind {
+    /// Constant force of `level` between -1.0 and 1.0.
+    Constant {
+        /// Force, between -1.0 and 1.0.
+        level: f32,
+        /// Attack and fade.
+        envelope: Envelope,
+    },
+    /// Force changing linearly from `start` to `end`, between -1.0 and 1.0.
+    Ramp {
+        /// Force at the start, between -1.0 and 1.0.
+        start: f32,
+        /// Force at the end, between -1.0 and 1.0.
+        end: f32,
+        /// Attack and fade.
+        envelope: Envelope,
+    },
+    /// Force following a waveform.
+    Periodic {
+        /// Shape of the wave.
+        waveform: Waveform,
+        /// Duration of one wave.
+        period: Duration,
+        /// Peak force, between -1.0 and 1.0.
+        magnitude: f32,
+        /// Added to the wave, between -1.0 and 1.0.
+        offset: f32,
+        /// Where in the wave to start, in waves between 0.0 and 1.0.
+        phase: f32,
+        /// Attack and fade.
+        envelope: Envelope,
+    },
+    /// Force depending on the position or movement of the stick, one
+    /// [`ConditionAxis`] for each of the X and Y axes.
+    Condition {
+        /// What the force depends on.
+        condition: Condition,
+        /// X and Y axis parameters.
+        axes: [ConditionAxis; 2],
+    },
+    /// Strong (low frequency, usually left) and weak (high frequency, usually
+    /// right) motor power, between 0.0 and 1.0.
+    Rumble {
+        /// Strong motor power, between 0.0 and 1.0.
+        strong: f32,
+        /// Weak motor power, between 0.0 and 1.0.
+        weak: f32,
+    },
+}
+
+/// Waveform of a [`EffectKind::Periodic`] effect.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Waveform {
+    /// Square wave.
+    Square,
+    /// Triangle wave.
+    Triangle,
+    /// Sine wave.
+    Sine,
+    /// Rising sawtooth wave.
+    SawUp,
+    /// Falling sawtooth wave.
+    SawDown,
+}
+
+/// What the force of a [`EffectKind::Condition`] effect depends on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Condition {
+    /// Pulls the stick towards the center, harder the further it is.
+    Spring,
+    /// Resists any movement of the stick.
+    Friction,
+    /// Resists movement of the stick, harder the faster it moves.
+    Damper,
+    /// Resists changes in speed of the stick.
+    Inertia,
+}
+
+/// Parameters of a [`EffectKind::Condition`] effect along one axis.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct ConditionAxis {
+    /// Maximum force right of the center, between 0.0 and 1.0.
+    pub right_saturation: f32,
+    /// Maximum force left of the center, between 0.0 and 1.0.
+    pub left_saturation: f32,
+    /// How fast the force grows right of the center, between -1.0 and 1.0.
+    pub right_coeff: f32,
+    /// How fast the force grows left of the center, between -1.0 and 1.0.
+    pub left_coeff: f32,
+    /// Size of the dead zone around the center, between 0.0 and 1.0.
+    pub deadband: f32,
+    /// Position of the center, between -1.0 and 1.0.
+    pub center: f32,
+}
+
+/// Fade in and out of an effect.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Envelope {
+    /// How long the effect takes to go from `attack_level` to full force.
+    pub attack_length: Duration,
+    /// Force at the start, between 0.0 and 1.0.
+    pub attack_level: f32,
+    /// How long the effect takes to go from full force to `fade_level`.
+    pub fade_length: Duration,
+    /// Force at the end, between 0.0 and 1.0.
+    pub fade_level: f32,
+}
+
+/// Handle of an effect uploaded to a controller.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EffectId(pub(crate) i16);
+
+/// The force feedback effects a controller can play.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct EffectSupport {
+    pub(crate) rumble: bool,
+    pub(crate) constant: bool,
+    pub(crate) ramp: bool,
+    pub(crate) periodic: bool,
+    pub(crate) waveforms: [bool; 5],
+    pub(crate) conditions: [bool; 4],
+    pub(crate) slots: usize,
+}
+
+impl EffectSupport {
+    /// Whether the controller can play `kind`, including its waveform or
+    /// condition.
+    pub fn supports(&self, kind: &EffectKind) -> bool {
+        match kind {
+            EffectKind::Constant { .. } => self.constant,
+            EffectKind::Ramp { .. } => self.ramp,
+            EffectKind::Periodic { waveform, .. } => {
+                self.periodic && self.waveforms[*waveform as usize]
+            }
+            EffectKind::Condition { condition, .. } => {
+                self.conditions[*condition as usize]
+            }
+            EffectKind::Rumble { .. } => self.rumble,
+        }
+    }
+
+    /// Whether the controller can rumble, with
+    /// [`Controller::rumble()`](crate::Controller::rumble) or a
+    /// [`EffectKind::Rumble`] effect.
+    pub fn rumble(&self) -> bool {
+        self.rumble
+    }
+
+    /// Whether the controller can play any effect at all.
+    pub fn is_empty(&self) -> bool {
+        !(self.rumble || self.constant || self.ramp || self.periodic)
+            && !self.conditions.contains(&true)
+    }
+
+    /// How many effects can be uploaded at the same time.
+    pub fn slots(&self) -> usize {
+        self.slots
+    }
+}
@@ -242,8 +242,10 @@ impl Event {
         Self::from_id(new_id, self.to_id().1)
     }
 
+    /// Build an event from its numeric id and value, the inverse of
+    /// [`Event::to_id`].
     #[inline(always)]
-    fn from_id(id: u8, value: f64) -> Self {
+    pub fn from_id(id: u8, value: f64) -> Self {
         match id {
             0x00 => Event::Disconnect,
             0x01 => Event::Exit(value != 0.0),
@@ -345,8 +347,10 @@ impl Event {
         }
     }
 
+    /// Get the numeric id and value of this event.  Buttons have a value of
+    /// `0.0` or `1.0`, `Disconnect` has a value of NaN.
     #[inline(always)]
-    pub(crate) fn to_id(self) -> (u8, f64) {
+    pub fn to_id(self) -> (u8, f64) {
         use Event::*;
         match self {
             Disconnect => (0x00, f64::NAN),
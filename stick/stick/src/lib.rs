@@ -122,12 +122,21 @@
 extern crate log;
 
 mod ctlr;
+mod device;
+mod effect;
 mod event;
 mod focus;
 mod listener;
 mod raw;
+mod raw_event;
 
-pub use ctlr::{Controller, Remap};
+pub use ctlr::{AxisInfo, Controller, Remap};
+pub use device::{probe_devices, tried_devices, DeviceAccess, DeviceNode};
+pub use effect::{
+    Condition, ConditionAxis, Effect, EffectId, EffectKind, EffectSupport,
+    Envelope, Waveform,
+};
 pub use event::Event;
 pub use focus::{focus, unfocus};
 pub use listener::Listener;
+pub use raw_event::RawEvent;
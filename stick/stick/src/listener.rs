@@ -12,6 +12,7 @@
 use crate::Remap;
 use std::fmt::Debug;
 use std::future::Future;
+use std::io;
 use std::pin::Pin;
 use std::task::Context;
 use std::task::Poll;
@@ -33,8 +34,41 @@ impl Default for Listener {
 
 impl Listener {
     /// Create a new listener for when new controllers are plugged in.
+    ///
+    /// # Panics
+    /// If the platform can't watch for controllers, see
+    /// [`try_new()`](Self::try_new).
     pub fn new(remap: Remap) -> Self {
-        Self(crate::raw::GLOBAL.with(|g| g.listener(remap)))
+        Self::with_all_devices(remap, false)
+    }
+
+    /// Create a new listener like [`new()`](Self::new), but if
+    /// `all_devices` is set also yield input devices that don't look like
+    /// game controllers (keyboards, mice, power buttons…), for debugging.
+    ///
+    /// # Panics
+    /// If the platform can't watch for controllers, see
+    /// [`try_with_all_devices()`](Self::try_with_all_devices).
+    pub fn with_all_devices(remap: Remap, all_devices: bool) -> Self {
+        Self::try_with_all_devices(remap, all_devices)
+            .expect("Couldn't watch for controllers")
+    }
+
+    /// Create a new listener like [`new()`](Self::new), returning an error
+    /// if the platform can't watch for controllers (on Linux, when inotify
+    /// or `/dev/input/` are unavailable).
+    pub fn try_new(remap: Remap) -> io::Result<Self> {
+        Self::try_with_all_devices(remap, false)
+    }
+
+    /// Fallible [`with_all_devices()`](Self::with_all_devices).
+    pub fn try_with_all_devices(
+        remap: Remap,
+        all_devices: bool,
+    ) -> io::Result<Self> {
+        crate::raw::GLOBAL
+            .with(|g| g.listener(remap, all_devices))
+            .map(Self)
     }
 }
 
@@ -10,7 +10,8 @@
 
 #![allow(unsafe_code)]
 
-use crate::{Event, Remap};
+use crate::{AxisInfo, DeviceNode, Effect, EffectSupport, Event, RawEvent, Remap};
+use std::io;
 use std::task::{Context, Poll};
 
 #[cfg_attr(
@@ -76,16 +77,55 @@ pub(crate) trait Controller: Send {
     }
     /// Stereo rumble effect (left is low frequency, right is high frequency).
     fn rumble(&mut self, _left: f32, _right: f32) {}
+    /// Force feedback effects this controller can play.
+    fn effects(&self) -> EffectSupport {
+        EffectSupport::default()
+    }
+    /// Upload an effect, replacing effect `id` if it isn't -1, and return
+    /// its id.
+    fn upload(&mut self, _effect: &Effect, _id: i16) -> io::Result<i16> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+    /// Play an uploaded effect `count` times, or stop it if `count` is 0.
+    fn play(&mut self, _id: i16, _count: u16) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+    /// Remove an uploaded effect.
+    fn erase(&mut self, _id: i16) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
     /// Get the name of this controller.
     fn name(&self) -> &str {
         "Unknown"
     }
-    /// Floating Point Translation for pressure axis/buttons.
-    fn pressure(&self, input: f64) -> f64 {
+    /// Get the device path of this controller, if there is one.
+    fn path(&self) -> Option<&str> {
+        None
+    }
+    /// Get the serial number of this controller, if it reports one.
+    fn serial(&self) -> Option<&str> {
+        None
+    }
+    /// Get where this controller is plugged in, if it reports it.
+    fn location(&self) -> Option<&str> {
+        None
+    }
+    /// Raw ranges of the analog axes, by the event id they produce.
+    fn axes(&self) -> Vec<AxisInfo> {
+        Vec::new()
+    }
+    /// Start or stop keeping the raw events read.
+    fn keep_raw_events(&mut self, _keep: bool) {}
+    /// Raw events read since the last call, oldest first.
+    fn raw_events(&mut self) -> Vec<RawEvent> {
+        Vec::new()
+    }
+    /// Floating Point Translation for pressure axis/buttons of event `_id`.
+    fn pressure(&self, _id: u8, input: f64) -> f64 {
         input
     }
-    /// Floating Point Translation for full axis values.
-    fn axis(&self, input: f64) -> f64 {
+    /// Floating Point Translation for full axis values of event `_id`.
+    fn axis(&self, _id: u8, input: f64) -> f64 {
         input
     }
 }
@@ -96,9 +136,22 @@ pub(crate) trait Global: std::any::Any {
     fn enable(&self) {}
     /// Disable all events (when window leaves focus).
     fn disable(&self) {}
-    /// Create a new listener.
-    fn listener(&self, _remap: Remap) -> Box<dyn Listener> {
-        Box::new(FakeListener)
+    /// Create a new listener, yielding non-controller devices too if
+    /// `all_devices`.
+    fn listener(
+        &self,
+        _remap: Remap,
+        _all_devices: bool,
+    ) -> io::Result<Box<dyn Listener>> {
+        Ok(Box::new(FakeListener))
+    }
+    /// Try every device node like a listener does.
+    fn probe_devices(&self) -> io::Result<Vec<DeviceNode>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+    /// Last outcome of each device node tried.
+    fn tried_devices(&self) -> Vec<DeviceNode> {
+        Vec::new()
     }
 }
 
@@ -9,15 +9,22 @@
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).  This file may not be copied,
 // modified, or distributed except according to those terms.
 
-use crate::{Event, Remap};
+use crate::{
+    AxisInfo, ConditionAxis, DeviceAccess, DeviceNode, Effect, EffectKind,
+    EffectSupport, Envelope, Event, RawEvent, Remap,
+};
 use smelling_salts::{Device, Watcher};
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::convert::TryInto;
 use std::fs::read_dir;
+use std::io;
 use std::mem::{size_of, MaybeUninit};
 use std::os::raw::{c_char, c_int, c_long, c_uint, c_ulong, c_ushort, c_void};
 use std::os::unix::io::RawFd;
+use std::sync::Mutex;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 // Event codes taken from
 // https://github.com/torvalds/linux/blob/master/include/uapi/linux/input-event-codes.h
@@ -332,6 +339,14 @@ struct TimeVal {
     tv_usec: c_long,
 }
 
+/// Raw events kept between two calls to `raw_events()`, at most.
+const RAW_EVENTS_MAX: usize = 256;
+
+/// Range assumed for a stick axis without usable absinfo, -32767 to 32767.
+const DEFAULT_AXIS_MAX: f64 = 32767.0;
+/// Range assumed for a trigger without usable absinfo, 0 to 255.
+const DEFAULT_PRESSURE_MAX: f64 = 255.0;
+
 #[repr(C)]
 struct EvdevEv {
     // struct input_event, from C.
@@ -344,6 +359,7 @@ struct EvdevEv {
 }
 
 #[repr(C)]
+#[derive(Copy, Clone)]
 struct AbsInfo {
     // struct input_absinfo, from C.
     value: i32,
@@ -449,11 +465,11 @@ struct FfRumbleEffect {
 
 #[repr(C)]
 union FfUnion {
-    constant: FfConstantEffect, // Not supported.
+    constant: FfConstantEffect,
     ramp: FfRampEffect,
     periodic: FfPeriodicEffect,
     condition: [FfConditionEffect; 2], /* One for each axis */
-    rumble: FfRumbleEffect,            // Not supported
+    rumble: FfRumbleEffect,
 }
 
 #[repr(C)]
@@ -529,6 +545,262 @@ fn joystick_haptic(fd: RawFd, id: i16, strong: f32, weak: f32) -> i16 {
     }
 }
 
+// Effect types and waveforms, from input.h.
+const FF_RUMBLE: u16 = 0x50;
+const FF_PERIODIC: u16 = 0x51;
+const FF_CONSTANT: u16 = 0x52;
+const FF_SPRING: u16 = 0x53;
+const FF_RAMP: u16 = 0x57;
+const FF_SQUARE: u16 = 0x58;
+
+// Query which effects are supported, EVIOCGBIT(EV_FF, 16) and EVIOCGEFFECTS.
+fn joystick_effects(fd: RawFd) -> EffectSupport {
+    let mut bits = [0u8; 16];
+    if unsafe { ioctl(fd, 0x8010_4535, bits.as_mut_ptr().cast()) } == -1 {
+        return EffectSupport::default();
+    }
+    let has = |code: u16| bits[code as usize / 8] & (1 << (code % 8)) != 0;
+
+    let mut slots: c_int = 0;
+    let slots_ptr: *mut c_int = &mut slots;
+    if unsafe { ioctl(fd, 0x8004_4584, slots_ptr.cast()) } == -1 {
+        slots = 0;
+    }
+
+    EffectSupport {
+        rumble: has(FF_RUMBLE),
+        constant: has(FF_CONSTANT),
+        ramp: has(FF_RAMP),
+        periodic: has(FF_PERIODIC),
+        waveforms: [0, 1, 2, 3, 4].map(|i| has(FF_SQUARE + i)),
+        conditions: [0, 1, 2, 3].map(|i| has(FF_SPRING + i)),
+        slots: slots.max(0) as usize,
+    }
+}
+
+// Force between -1.0 and 1.0.
+fn ff_level(level: f32) -> i16 {
+    (level.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16
+}
+
+// Magnitude between 0.0 and 1.0.
+fn ff_magnitude(magnitude: f32) -> u16 {
+    (magnitude.clamp(0.0, 1.0) * f32::from(u16::MAX)) as u16
+}
+
+// Fraction of a turn (direction or phase).
+fn ff_turns(turns: f32) -> u16 {
+    (turns.rem_euclid(1.0) * 65536.0) as u16
+}
+
+fn ff_millis(duration: Duration) -> u16 {
+    duration.as_millis().min(u16::MAX.into()) as u16
+}
+
+fn ff_envelope(envelope: &Envelope) -> FfEnvelope {
+    // Envelope levels go up to 0x7FFF.
+    FfEnvelope {
+        attack_length: ff_millis(envelope.attack_length),
+        attack_level: ff_magnitude(envelope.attack_level) / 2,
+        fade_length: ff_millis(envelope.fade_length),
+        fade_level: ff_magnitude(envelope.fade_level) / 2,
+    }
+}
+
+fn ff_condition(axis: &ConditionAxis) -> FfConditionEffect {
+    FfConditionEffect {
+        right_saturation: ff_magnitude(axis.right_saturation),
+        left_saturation: ff_magnitude(axis.left_saturation),
+        right_coeff: ff_level(axis.right_coeff),
+        left_coeff: ff_level(axis.left_coeff),
+        deadband: ff_magnitude(axis.deadband),
+        center: ff_level(axis.center),
+    }
+}
+
+// Convert a stick Effect to struct ff_effect, with effect id `id`.
+fn linux_ff_effect(effect: &Effect, id: i16) -> FfEffect {
+    let (stype, u) = match effect.kind {
+        EffectKind::Constant { level, envelope } => (
+            FF_CONSTANT,
+            FfUnion {
+                constant: FfConstantEffect {
+                    level: ff_level(level),
+                    envelope: ff_envelope(&envelope),
+                },
+            },
+        ),
+        EffectKind::Ramp {
+            start,
+            end,
+            envelope,
+        } => (
+            FF_RAMP,
+            FfUnion {
+                ramp: FfRampEffect {
+                    start_level: ff_level(start),
+                    end_level: ff_level(end),
+                    envelope: ff_envelope(&envelope),
+                },
+            },
+        ),
+        EffectKind::Periodic {
+            waveform,
+            period,
+            magnitude,
+            offset,
+            phase,
+            envelope,
+        } => (
+            FF_PERIODIC,
+            FfUnion {
+                periodic: FfPeriodicEffect {
+                    waveform: FF_SQUARE + waveform as u16,
+                    period: ff_millis(period),
+                    magnitude: ff_level(magnitude),
+                    offset: ff_level(offset),
+                    phase: ff_turns(phase),
+                    envelope: ff_envelope(&envelope),
+                    custom_len: 0,
+                    custom_data: std::ptr::null_mut(),
+                },
+            },
+        ),
+        EffectKind::Condition { condition, axes } => (
+            FF_SPRING + condition as u16,
+            FfUnion {
+                condition: [ff_condition(&axes[0]), ff_condition(&axes[1])],
+            },
+        ),
+        EffectKind::Rumble { strong, weak } => (
+            FF_RUMBLE,
+            FfUnion {
+                rumble: FfRumbleEffect {
+                    strong_magnitude: ff_magnitude(strong),
+                    weak_magnitude: ff_magnitude(weak),
+                },
+            },
+        ),
+    };
+
+    FfEffect {
+        stype,
+        id,
+        direction: ff_turns(effect.direction),
+        trigger: FfTrigger {
+            button: 0,
+            interval: 0,
+        },
+        replay: FfReplay {
+            length: ff_millis(effect.length),
+            delay: ff_millis(effect.delay),
+        },
+        u,
+    }
+}
+
+// Normalization of one absolute axis, from its own absinfo.
+struct AbsAxis {
+    // Stick event id the axis produces.
+    event: u8,
+    info: AbsInfo,
+    /// Signed axis multiplier
+    norm: f64,
+    /// Signed axis zero
+    zero: f64,
+    /// Don't process near 0
+    flat: f64,
+}
+
+impl AbsAxis {
+    fn new(event: u8, info: AbsInfo) -> Self {
+        let norm = (info.maximum as f64 - info.minimum as f64) * 0.5;
+        let zero = info.minimum as f64 + norm;
+        // Invert so multiplication can be used instead of division
+        let norm = norm.recip();
+        let flat = info.flat as f64 * norm;
+
+        Self {
+            event,
+            info,
+            norm,
+            zero,
+            flat,
+        }
+    }
+
+    // Full axis range, -1.0 to 1.0.
+    fn axis(&self, input: f64) -> f64 {
+        let input = (input - self.zero) * self.norm;
+        if input.abs() <= self.flat {
+            0.0
+        } else {
+            input
+        }
+    }
+
+    // Pressure range, 0.0 to 1.0.  A trigger rests at its minimum, so the
+    // flat zone is measured from there rather than around the middle.
+    fn pressure(&self, input: f64) -> f64 {
+        let input = (input - self.info.minimum as f64) * self.norm * 0.5;
+        if input <= self.flat * 0.5 {
+            0.0
+        } else {
+            input
+        }
+    }
+}
+
+// Query the range of every analog axis, EVIOCGBIT(EV_ABS, 8) and EVIOCGABS.
+fn joystick_axes(fd: RawFd) -> Vec<AbsAxis> {
+    let mut bits = [0u8; 8];
+    if unsafe { ioctl(fd, 0x8008_4523, bits.as_mut_ptr().cast()) } == -1 {
+        return Vec::new();
+    }
+
+    let mut axes = Vec::new();
+    // Hats (0x10 and up) are reported as buttons, so only axes below them.
+    for code in 0x00..0x10u16 {
+        if bits[code as usize / 8] & (1 << (code % 8)) == 0 {
+            continue;
+        }
+
+        let mut a = MaybeUninit::<AbsInfo>::uninit();
+        let request = 0x_8018_4540 + c_ulong::from(code);
+        if unsafe { ioctl(fd, request, a.as_mut_ptr().cast()) } == -1 {
+            continue;
+        }
+        let a = unsafe { a.assume_init() };
+        if a.maximum <= a.minimum {
+            continue;
+        }
+
+        // Find out which event the axis produces.
+        let mut pending = Vec::new();
+        linux_abs_to_stick_event(&mut pending, code, 0);
+        if let Some(event) = pending.first() {
+            axes.push(AbsAxis::new(event.to_id().0, a));
+        }
+    }
+    axes
+}
+
+// A string property of the device (EVIOCGUNIQ, EVIOCGPHYS…), `None` if it
+// has none.
+fn joystick_string(fd: RawFd, request: c_ulong) -> Option<String> {
+    let mut a: [c_char; 256] = [0; 256];
+    if unsafe { ioctl(fd, request, a.as_mut_ptr().cast()) } == -1 {
+        return None;
+    }
+    let string = unsafe { std::ffi::CStr::from_ptr(a.as_ptr()) };
+    let string = string.to_string_lossy();
+    if string.is_empty() {
+        None
+    } else {
+        Some(string.to_string())
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 /// Gamepad / Other HID
@@ -539,73 +811,89 @@ struct Controller {
     id: u64,
     // Rumble effect id.
     rumble: i16,
-    /// Signed axis multiplier
-    norm: f64,
-    /// Signed axis zero
-    zero: f64,
-    /// Don't process near 0
-    flat: f64,
+    // Supported force feedback effects.
+    effects: EffectSupport,
+    /// Range of each analog axis
+    axes: Vec<AbsAxis>,
     ///
     pending_events: Vec<Event>,
     ///
     name: String,
+    /// Device node this controller was opened from
+    path: String,
+    /// Serial number or Bluetooth address, if the device reports one
+    serial: Option<String>,
+    /// Physical location, like the USB port, if the device reports one
+    location: Option<String>,
+    /// Raw events read, if they are kept
+    raw_events: Option<VecDeque<RawEvent>>,
 }
 
 impl Controller {
-    fn new(fd: c_int) -> Self {
+    /// Takes ownership of `fd`, closing it if the device can't be set up.
+    fn new(fd: c_int, path: String) -> io::Result<Self> {
+        let fail = || {
+            let err = io::Error::last_os_error();
+            unsafe { close(fd) };
+            Err(err)
+        };
+
         // Enable evdev async.
-        assert_ne!(unsafe { fcntl(fd, 0x4, 0x800) }, -1);
+        if unsafe { fcntl(fd, 0x4, 0x800) } == -1 {
+            return fail();
+        }
 
         // Get the hardware id of this controller.
         let mut id = MaybeUninit::<u64>::uninit();
-        assert_ne!(
-            unsafe { ioctl(fd, 0x_8008_4502, id.as_mut_ptr().cast()) },
-            -1
-        );
+        if unsafe { ioctl(fd, 0x_8008_4502, id.as_mut_ptr().cast()) } == -1 {
+            return fail();
+        }
         let id = unsafe { id.assume_init() }.to_be();
 
-        // Get the min and max absolute values for axis.
-        let mut a = MaybeUninit::<AbsInfo>::uninit();
-        assert_ne!(
-            unsafe { ioctl(fd, 0x_8018_4540, a.as_mut_ptr().cast()) },
-            -1
-        );
+        // Get Name
+        let mut a = MaybeUninit::<[c_char; 256]>::uninit();
+        if unsafe { ioctl(fd, 0x80FF_4506, a.as_mut_ptr().cast()) } == -1 {
+            return fail();
+        }
         let a = unsafe { a.assume_init() };
-        let norm = (a.maximum as f64 - a.minimum as f64) * 0.5;
-        let zero = a.minimum as f64 + norm;
-        // Invert so multiplication can be used instead of division
-        let norm = norm.recip();
-        let flat = a.flat as f64 * norm;
+        let name = unsafe { std::ffi::CStr::from_ptr(a.as_ptr()) };
+        let name = name.to_string_lossy().to_string();
+
+        // Get what tells the device apart from identical ones.
+        let serial = joystick_string(fd, 0x80FF_4508);
+        let location = joystick_string(fd, 0x80FF_4507);
+
+        // Get the min and max absolute values for each axis.
+        let axes = joystick_axes(fd);
 
         // Query the controller for haptic support.
         let rumble = joystick_haptic(fd, -1, 0.0, 0.0);
+        // Effects are uploaded and played by writing, so a device opened
+        // read-only can't play any (F_GETFL & O_ACCMODE == O_RDONLY).
+        let effects = if unsafe { fcntl(fd, 0x3, 0) } & 0x3 == 0 {
+            EffectSupport::default()
+        } else {
+            joystick_effects(fd)
+        };
         // Construct device from fd, looking for input events.
         let device = Device::new(fd, Watcher::new().input());
         //
         let pending_events = Vec::new();
 
-        // Get Name
-        let fd = device.raw();
-        let mut a = MaybeUninit::<[c_char; 256]>::uninit();
-        assert_ne!(
-            unsafe { ioctl(fd, 0x80FF_4506, a.as_mut_ptr().cast()) },
-            -1
-        );
-        let a = unsafe { a.assume_init() };
-        let name = unsafe { std::ffi::CStr::from_ptr(a.as_ptr()) };
-        let name = name.to_string_lossy().to_string();
-
         // Return
-        Self {
+        Ok(Self {
             device,
             id,
             rumble,
-            norm,
-            zero,
-            flat,
+            effects,
+            axes,
             pending_events,
             name,
-        }
+            path,
+            serial,
+            location,
+            raw_events: None,
+        })
     }
 }
 
@@ -648,6 +936,24 @@ impl super::Controller for Controller {
             unsafe { ev.assume_init() }
         };
 
+        // Keep the event as read, syncs aside.
+        if let Some(raw_events) = &mut self.raw_events {
+            if ev.ev_type != 0x00 {
+                if raw_events.len() == RAW_EVENTS_MAX {
+                    raw_events.pop_front();
+                }
+                raw_events.push_back(RawEvent {
+                    kind: ev.ev_type,
+                    code: ev.ev_code,
+                    value: ev.ev_value,
+                    time: Duration::new(
+                        ev.ev_time.tv_sec as u64,
+                        ev.ev_time.tv_usec as u32 * 1000,
+                    ),
+                });
+            }
+        }
+
         // Convert the event (may produce multiple stick events).
         linux_evdev_to_stick_event(&mut self.pending_events, ev);
 
@@ -664,24 +970,115 @@ impl super::Controller for Controller {
         &self.name
     }
 
+    fn path(&self) -> Option<&str> {
+        Some(&self.path)
+    }
+
+    fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+
+    fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    fn keep_raw_events(&mut self, keep: bool) {
+        match (keep, &self.raw_events) {
+            (true, None) => {
+                self.raw_events = Some(VecDeque::with_capacity(RAW_EVENTS_MAX))
+            }
+            (false, Some(_)) => self.raw_events = None,
+            _ => {}
+        }
+    }
+
+    fn raw_events(&mut self) -> Vec<RawEvent> {
+        match &mut self.raw_events {
+            Some(raw_events) => raw_events.drain(..).collect(),
+            None => Vec::new(),
+        }
+    }
+
     fn rumble(&mut self, left: f32, right: f32) {
         if self.rumble >= 0 {
             joystick_ff(self.device.raw(), self.rumble, left, right);
         }
     }
 
-    /// Use default unsigned axis range
-    fn pressure(&self, input: f64) -> f64 {
-        input * (1.0 / 255.0)
+    fn effects(&self) -> EffectSupport {
+        self.effects
+    }
+
+    fn upload(&mut self, effect: &Effect, id: i16) -> io::Result<i16> {
+        // EVIOCSFF
+        let ff = &mut linux_ff_effect(effect, id);
+        let ff_ptr: *mut _ = ff;
+        if unsafe { ioctl(self.device.raw(), 0x4030_4580, ff_ptr.cast()) } == -1
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(ff.id)
+    }
+
+    fn play(&mut self, id: i16, count: u16) -> io::Result<()> {
+        let ev_code = id
+            .try_into()
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let play = &EvdevEv {
+            ev_time: TimeVal {
+                tv_sec: 0,
+                tv_usec: 0,
+            },
+            ev_type: 0x15, /*EV_FF*/
+            ev_code,
+            ev_value: count.into(),
+        };
+        let play: *const _ = play;
+        let written = unsafe {
+            write(self.device.raw(), play.cast(), size_of::<EvdevEv>())
+        };
+        if written != size_of::<EvdevEv>() as isize {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn erase(&mut self, id: i16) -> io::Result<()> {
+        // EVIOCRMFF takes the effect id itself, not a pointer to it.
+        let id = c_int::from(id) as usize as *mut c_void;
+        if unsafe { ioctl(self.device.raw(), 0x4004_4581, id) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn axes(&self) -> Vec<AxisInfo> {
+        self.axes
+            .iter()
+            .map(|axis| AxisInfo {
+                event: axis.event,
+                minimum: axis.info.minimum,
+                maximum: axis.info.maximum,
+                fuzz: axis.info.fuzz,
+                flat: axis.info.flat,
+                resolution: axis.info.resolution,
+            })
+            .collect()
+    }
+
+    /// Use the axis range, or default unsigned axis range if unknown
+    fn pressure(&self, event: u8, input: f64) -> f64 {
+        match self.axes.iter().find(|axis| axis.event == event) {
+            Some(axis) => axis.pressure(input),
+            None => input * DEFAULT_PRESSURE_MAX.recip(),
+        }
     }
 
     /// Use full joystick axis range.
-    fn axis(&self, input: f64) -> f64 {
-        let input = (input - self.zero) * self.norm;
-        if input.abs() <= self.flat {
-            0.0
-        } else {
-            input
+    fn axis(&self, event: u8, input: f64) -> f64 {
+        match self.axes.iter().find(|axis| axis.event == event) {
+            Some(axis) => axis.axis(input),
+            None => input * DEFAULT_AXIS_MAX.recip(),
         }
     }
 }
@@ -692,14 +1089,114 @@ impl Drop for Controller {
     }
 }
 
+// Whether the device looks like a game controller, from its EV_KEY and
+// EV_ABS capabilities (EVIOCGBIT) and its properties (EVIOCGPROP).
+fn joystick_capable(fd: RawFd) -> bool {
+    // The motion sensors of DualShock 4, DualSense and Switch Pro
+    // controllers are separate nodes with ABS_X and ABS_Y, but they are
+    // flagged INPUT_PROP_ACCELEROMETER.
+    let mut props = [0u8; 4];
+    if unsafe { ioctl(fd, 0x8004_4509, props.as_mut_ptr().cast()) } == -1 {
+        props = [0; 4];
+    }
+    if props[0] & (1 << 0x06) != 0 {
+        return false;
+    }
+
+    let mut keys = [0u8; 96];
+    if unsafe { ioctl(fd, 0x8060_4521, keys.as_mut_ptr().cast()) } == -1 {
+        keys = [0; 96];
+    }
+    let mut abs = [0u8; 8];
+    if unsafe { ioctl(fd, 0x8008_4523, abs.as_mut_ptr().cast()) } == -1 {
+        abs = [0; 8];
+    }
+    let has_key = |code: usize| keys[code / 8] & (1 << (code % 8)) != 0;
+    let has_abs = |code: usize| abs[code / 8] & (1 << (code % 8)) != 0;
+
+    // BTN_JOYSTICK.., BTN_GAMEPAD.. and BTN_TRIGGER_HAPPY..
+    let buttons = (0x120..=0x12F).any(has_key)
+        || (0x130..=0x13E).any(has_key)
+        || (0x2C0..=0x2E7).any(has_key);
+    // ABS_X and ABS_Y, but not from a mouse (BTN_MOUSE), touchpad
+    // (BTN_TOUCH) or tablet (BTN_TOOL_PEN).
+    let stick = has_abs(0x00)
+        && has_abs(0x01)
+        && !has_key(0x110)
+        && !has_key(0x14A)
+        && !has_key(0x140);
+
+    buttons || stick
+}
+
+/// Last outcome of each device node tried, for `tried_devices()`.
+static TRIED: Mutex<Vec<DeviceNode>> = Mutex::new(Vec::new());
+
+// Open a device node the way listeners do, and remember how that went.
+fn open_node(path: &str) -> (DeviceNode, Option<RawFd>) {
+    let mut filename = path.to_string();
+    filename.push('\0');
+    // Try read & write first
+    let mut access = DeviceAccess::ReadWrite;
+    let mut fd = unsafe { open(filename.as_ptr(), 2) };
+    // Try readonly second (bluetooth controller - input device)
+    if fd == -1 {
+        access = DeviceAccess::ReadOnly;
+        fd = unsafe { open(filename.as_ptr(), 0) };
+    }
+    // Try writeonly third (bluetooth haptic device)
+    if fd == -1 {
+        access = DeviceAccess::WriteOnly;
+        fd = unsafe { open(filename.as_ptr(), 1) };
+    }
+
+    let node = if fd == -1 {
+        let err = io::Error::last_os_error();
+        log::debug!("Couldn't open {}: {}", path, err);
+        DeviceNode {
+            path: path.to_string(),
+            access: Err(err.raw_os_error().unwrap_or_default()),
+            controller: None,
+        }
+    } else {
+        DeviceNode {
+            path: path.to_string(),
+            access: Ok(access),
+            controller: Some(joystick_capable(fd)),
+        }
+    };
+
+    record_node(&node);
+
+    (node, if fd == -1 { None } else { Some(fd) })
+}
+
+// Remember the last outcome for a device node.
+fn record_node(node: &DeviceNode) {
+    let mut tried = TRIED.lock().unwrap_or_else(|e| e.into_inner());
+    match tried.iter_mut().find(|tried| tried.path == node.path) {
+        Some(tried) => *tried = node.clone(),
+        None => tried.push(node.clone()),
+    }
+}
+
+fn close_node(path: &str, fd: RawFd) {
+    // Nothing was read from it, a failed close loses nothing.
+    if unsafe { close(fd) } == -1 {
+        log::debug!("Closing {}: {}", path, io::Error::last_os_error());
+    }
+}
+
 struct Listener {
     device: Device,
     read_dir: Option<Box<std::fs::ReadDir>>,
     remap: Remap,
+    // Yield devices that don't look like game controllers too.
+    all_devices: bool,
 }
 
 impl Listener {
-    fn new(remap: Remap) -> Self {
+    fn new(remap: Remap, all_devices: bool) -> io::Result<Self> {
         const CLOEXEC: c_int = 0o2000000;
         const NONBLOCK: c_int = 0o0004000;
         const ATTRIB: c_uint = 0x00000004;
@@ -708,49 +1205,66 @@ impl Listener {
         // Create an inotify.
         let listen = unsafe { inotify_init1(NONBLOCK | CLOEXEC) };
         if listen == -1 {
-            panic!("Couldn't create inotify!");
+            return Err(io::Error::last_os_error());
         }
 
         // Start watching the controller directory.
         if unsafe { inotify_add_watch(listen, DIR.as_ptr(), ATTRIB) } == -1 {
-            panic!("Couldn't add inotify watch!");
+            let err = io::Error::last_os_error();
+            unsafe { close(listen) };
+            return Err(err);
         }
 
-        Self {
+        let read_dir = match read_dir("/dev/input/") {
+            Ok(read_dir) => read_dir,
+            Err(err) => {
+                unsafe { close(listen) };
+                return Err(err);
+            }
+        };
+
+        Ok(Self {
             // Create watcher, and register with fd as a "device".
             device: Device::new(listen, Watcher::new().input()),
             //
-            read_dir: Some(Box::new(read_dir("/dev/input/").unwrap())),
+            read_dir: Some(Box::new(read_dir)),
             //
             remap,
-        }
+            all_devices,
+        })
     }
 
     fn controller(
         remap: &Remap,
-        mut filename: String,
+        all_devices: bool,
+        path: String,
     ) -> Poll<crate::Controller> {
-        if filename.contains("event") {
-            filename.push('\0');
-            // Try read & write first
-            let mut fd = unsafe { open(filename.as_ptr(), 2) };
-            // Try readonly second (bluetooth controller - input device)
-            if fd == -1 {
-                fd = unsafe { open(filename.as_ptr(), 0) };
-            }
-            // Try writeonly third (bluetooth haptic device)
-            if fd == -1 {
-                fd = unsafe { open(filename.as_ptr(), 1) };
+        if !path.contains("event") {
+            return Poll::Pending;
+        }
+        let (mut node, fd) = match open_node(&path) {
+            (_, None) => return Poll::Pending,
+            // Skip (and close) keyboards, switches and the like.
+            (node, Some(fd)) if !all_devices && node.controller != Some(true) => {
+                close_node(&path, fd);
+                return Poll::Pending;
             }
-            // If one succeeded, return that controller.
-            if fd != -1 {
-                return Poll::Ready(crate::Controller::new(
-                    Box::new(Controller::new(fd)),
-                    remap,
-                ));
+            (node, Some(fd)) => (node, fd),
+        };
+        match Controller::new(fd, path.clone()) {
+            Ok(controller) => Poll::Ready(crate::Controller::new(
+                Box::new(controller),
+                remap,
+            )),
+            // Odd device nodes are skipped, not fatal.
+            Err(err) => {
+                log::warn!("Skipping {}: {}", path, err);
+                // It opened, but isn't usable.
+                node.access = Err(err.raw_os_error().unwrap_or_default());
+                record_node(&node);
+                Poll::Pending
             }
         }
-        Poll::Pending
     }
 }
 
@@ -762,7 +1276,7 @@ impl super::Listener for Listener {
                 let file = dir_entry.path();
                 let path = file.as_path().to_string_lossy().to_string();
                 if let Poll::Ready(controller) =
-                    Self::controller(&self.remap, path)
+                    Self::controller(&self.remap, self.all_devices, path)
                 {
                     return Poll::Ready(controller);
                 }
@@ -784,7 +1298,8 @@ impl super::Listener for Listener {
             let len = unsafe { strlen(&ev.name[0]) };
             let filename = String::from_utf8_lossy(&ev.name[..len]);
             let path = format!("/dev/input/{}", filename);
-            if let Poll::Ready(controller) = Self::controller(&self.remap, path)
+            if let Poll::Ready(controller) =
+                Self::controller(&self.remap, self.all_devices, path)
             {
                 return Poll::Ready(controller);
             }
@@ -816,11 +1331,108 @@ impl super::Global for Global {
         ENABLED.store(false, std::sync::atomic::Ordering::Relaxed);
     }
     /// Create a new listener.
-    fn listener(&self, remap: Remap) -> Box<dyn super::Listener> {
-        Box::new(Listener::new(remap))
+    fn listener(
+        &self,
+        remap: Remap,
+        all_devices: bool,
+    ) -> io::Result<Box<dyn super::Listener>> {
+        Ok(Box::new(Listener::new(remap, all_devices)?))
+    }
+    /// Try every device node like a listener does.
+    fn probe_devices(&self) -> io::Result<Vec<DeviceNode>> {
+        let mut nodes = Vec::new();
+        for dir_entry in read_dir("/dev/input/")?.flatten() {
+            let path = dir_entry.path().to_string_lossy().to_string();
+            if !path.contains("event") {
+                continue;
+            }
+            let (node, fd) = open_node(&path);
+            if let Some(fd) = fd {
+                close_node(&path, fd);
+            }
+            nodes.push(node);
+        }
+        Ok(nodes)
+    }
+    /// Last outcome of each device node tried.
+    fn tried_devices(&self) -> Vec<DeviceNode> {
+        TRIED.lock().unwrap_or_else(|e| e.into_inner()).clone()
     }
 }
 
 pub(super) fn global() -> Box<dyn super::Global> {
     Box::new(Global)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_scale_to_the_full_range() {
+        assert_eq!(ff_level(1.0), i16::MAX);
+        assert_eq!(ff_level(-1.0), -i16::MAX);
+        assert_eq!(ff_level(0.5), 16383);
+        assert_eq!(ff_level(2.0), i16::MAX);
+        assert_eq!(ff_magnitude(1.0), u16::MAX);
+        assert_eq!(ff_magnitude(-0.5), 0);
+    }
+
+    #[test]
+    fn directions_are_fractions_of_a_turn() {
+        assert_eq!(ff_turns(0.0), 0);
+        assert_eq!(ff_turns(0.25), 0x4000);
+        assert_eq!(ff_turns(0.5), 0x8000);
+        assert_eq!(ff_turns(1.25), 0x4000);
+        assert_eq!(ff_turns(-0.25), 0xC000);
+    }
+
+    #[test]
+    fn constant_effect_converts_level_direction_and_envelope() {
+        let envelope = Envelope {
+            attack_length: Duration::from_millis(100),
+            attack_level: 1.0,
+            fade_length: Duration::from_secs(120),
+            fade_level: 0.5,
+        };
+        let mut effect = Effect::new(
+            EffectKind::Constant {
+                level: -0.5,
+                envelope,
+            },
+            Duration::from_millis(250),
+        );
+        effect.direction = 0.75;
+        let ff = linux_ff_effect(&effect, 3);
+
+        assert_eq!(ff.stype, FF_CONSTANT);
+        assert_eq!(ff.id, 3);
+        assert_eq!(ff.direction, 0xC000);
+        assert_eq!(ff.replay.length, 250);
+        assert_eq!(ff.replay.delay, 0);
+        let constant = unsafe { ff.u.constant };
+        assert_eq!(constant.level, -16383);
+        assert_eq!(constant.envelope.attack_length, 100);
+        assert_eq!(constant.envelope.attack_level, 0x7FFF);
+        assert_eq!(constant.envelope.fade_length, u16::MAX);
+        assert_eq!(constant.envelope.fade_level, 0x3FFF);
+    }
+
+    #[test]
+    fn rumble_effect_converts_magnitudes() {
+        let effect = Effect::new(
+            EffectKind::Rumble {
+                strong: 1.0,
+                weak: 0.0,
+            },
+            Duration::ZERO,
+        );
+        let ff = linux_ff_effect(&effect, -1);
+
+        assert_eq!(ff.stype, FF_RUMBLE);
+        assert_eq!(ff.id, -1);
+        let rumble = unsafe { ff.u.rumble };
+        assert_eq!(rumble.strong_magnitude, u16::MAX);
+        assert_eq!(rumble.weak_magnitude, 0);
+    }
+}
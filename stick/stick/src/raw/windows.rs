@@ -854,8 +854,12 @@ impl super::Global for Global {
         unsafe { (self.xinput.xinput_enable)(false as _) };
     }
     /// Create a new listener.
-    fn listener(&self, remap: Remap) -> Box<dyn super::Listener> {
-        Box::new(Listener::new(remap, self.xinput.clone()))
+    fn listener(
+        &self,
+        remap: Remap,
+        _all_devices: bool,
+    ) -> std::io::Result<Box<dyn super::Listener>> {
+        Ok(Box::new(Listener::new(remap, self.xinput.clone())))
     }
 }
 
@@ -0,0 +1,188 @@
+// Stick
+// Copyright © 2017-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your option (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).  This file may not be copied,
+// modified, or distributed except according to those terms.
+
+use std::time::Duration;
+
+/// An event as the hardware sent it, before it's turned into an
+/// [`Event`](crate::Event) and remapped.  On Linux, a `struct input_event`.
+///
+/// Kept only after [`Controller::keep_raw_events()`](crate::Controller::keep_raw_events).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RawEvent {
+    /// Event type (`EV_KEY`, `EV_ABS`…).
+    pub kind: u16,
+    /// Event code within its type (`BTN_SOUTH`, `ABS_RX`…).
+    pub code: u16,
+    /// Raw value: 0 or 1 for buttons, the raw axis position for axes.
+    pub value: i32,
+    /// Kernel timestamp, since the Unix epoch unless the device clock was
+    /// changed.
+    pub time: Duration,
+}
+
+impl RawEvent {
+    /// Name of the event type, like `EV_ABS`.
+    pub fn kind_name(&self) -> String {
+        let name = match self.kind {
+            0x00 => "EV_SYN",
+            0x01 => "EV_KEY",
+            0x02 => "EV_REL",
+            0x03 => "EV_ABS",
+            0x04 => "EV_MSC",
+            0x05 => "EV_SW",
+            0x11 => "EV_LED",
+            0x12 => "EV_SND",
+            0x14 => "EV_REP",
+            0x15 => "EV_FF",
+            0x16 => "EV_PWR",
+            0x17 => "EV_FF_STATUS",
+            kind => return format!("EV_{:#04x}", kind),
+        };
+        name.to_string()
+    }
+
+    /// Name of the event code, like `BTN_SOUTH` or `ABS_RX`, or its number
+    /// in hex if it has no well known name.
+    pub fn code_name(&self) -> String {
+        let name = match self.kind {
+            0x00 => syn_name(self.code),
+            0x01 => key_name(self.code),
+            0x02 => rel_name(self.code),
+            0x03 => abs_name(self.code),
+            0x04 => msc_name(self.code),
+            _ => None,
+        };
+        match name {
+            Some(name) => name,
+            None => format!("{:#06x}", self.code),
+        }
+    }
+}
+
+fn syn_name(code: u16) -> Option<String> {
+    let name = match code {
+        0 => "SYN_REPORT",
+        1 => "SYN_CONFIG",
+        2 => "SYN_MT_REPORT",
+        3 => "SYN_DROPPED",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+fn key_name(code: u16) -> Option<String> {
+    let name = match code {
+        0x100..=0x109 => return Some(format!("BTN_{}", code - 0x100)),
+        0x110 => "BTN_LEFT",
+        0x111 => "BTN_RIGHT",
+        0x112 => "BTN_MIDDLE",
+        0x113 => "BTN_SIDE",
+        0x114 => "BTN_EXTRA",
+        0x115 => "BTN_FORWARD",
+        0x116 => "BTN_BACK",
+        0x117 => "BTN_TASK",
+        0x120 => "BTN_TRIGGER",
+        0x121 => "BTN_THUMB",
+        0x122 => "BTN_THUMB2",
+        0x123 => "BTN_TOP",
+        0x124 => "BTN_TOP2",
+        0x125 => "BTN_PINKIE",
+        0x126 => "BTN_BASE",
+        0x127..=0x12B => return Some(format!("BTN_BASE{}", code - 0x125)),
+        0x12F => "BTN_DEAD",
+        0x130 => "BTN_SOUTH",
+        0x131 => "BTN_EAST",
+        0x132 => "BTN_C",
+        0x133 => "BTN_NORTH",
+        0x134 => "BTN_WEST",
+        0x135 => "BTN_Z",
+        0x136 => "BTN_TL",
+        0x137 => "BTN_TR",
+        0x138 => "BTN_TL2",
+        0x139 => "BTN_TR2",
+        0x13A => "BTN_SELECT",
+        0x13B => "BTN_START",
+        0x13C => "BTN_MODE",
+        0x13D => "BTN_THUMBL",
+        0x13E => "BTN_THUMBR",
+        0x140 => "BTN_TOOL_PEN",
+        0x14A => "BTN_TOUCH",
+        0x220 => "BTN_DPAD_UP",
+        0x221 => "BTN_DPAD_DOWN",
+        0x222 => "BTN_DPAD_LEFT",
+        0x223 => "BTN_DPAD_RIGHT",
+        0x2C0..=0x2E7 => {
+            return Some(format!("BTN_TRIGGER_HAPPY{}", code - 0x2BF))
+        }
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+fn rel_name(code: u16) -> Option<String> {
+    let name = match code {
+        0x00 => "REL_X",
+        0x01 => "REL_Y",
+        0x02 => "REL_Z",
+        0x03 => "REL_RX",
+        0x04 => "REL_RY",
+        0x05 => "REL_RZ",
+        0x06 => "REL_HWHEEL",
+        0x07 => "REL_DIAL",
+        0x08 => "REL_WHEEL",
+        0x09 => "REL_MISC",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+fn abs_name(code: u16) -> Option<String> {
+    let name = match code {
+        0x00 => "ABS_X",
+        0x01 => "ABS_Y",
+        0x02 => "ABS_Z",
+        0x03 => "ABS_RX",
+        0x04 => "ABS_RY",
+        0x05 => "ABS_RZ",
+        0x06 => "ABS_THROTTLE",
+        0x07 => "ABS_RUDDER",
+        0x08 => "ABS_WHEEL",
+        0x09 => "ABS_GAS",
+        0x0A => "ABS_BRAKE",
+        0x10..=0x17 => {
+            let axis = if code % 2 == 0 { 'X' } else { 'Y' };
+            return Some(format!("ABS_HAT{}{}", (code - 0x10) / 2, axis));
+        }
+        0x18 => "ABS_PRESSURE",
+        0x19 => "ABS_DISTANCE",
+        0x1A => "ABS_TILT_X",
+        0x1B => "ABS_TILT_Y",
+        0x1C => "ABS_TOOL_WIDTH",
+        0x20 => "ABS_VOLUME",
+        0x21 => "ABS_PROFILE",
+        0x28 => "ABS_MISC",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+fn msc_name(code: u16) -> Option<String> {
+    let name = match code {
+        0 => "MSC_SERIAL",
+        1 => "MSC_PULSELED",
+        2 => "MSC_GESTURE",
+        3 => "MSC_RAW",
+        4 => "MSC_SCAN",
+        5 => "MSC_TIMESTAMP",
+        _ => return None,
+    };
+    Some(name.to_string())
+}